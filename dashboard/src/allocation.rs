@@ -0,0 +1,93 @@
+// Water allocation and what pumping it costs
+//
+// Logged irrigation is gross inches over the field, so it's turned into acre-inches with the
+// field's area. Pumping energy is the work of lifting the water and pressurising it:
+// 27,154 gallons to an acre-inch, 8.34 lb a gallon, 2.31 ft of head for every psi.
+
+// Gallons in an acre-inch
+const ACRE_INCH_GALLONS: f64 = 27_154.0;
+// Foot-pounds in a kWh
+const FOOT_POUNDS_PER_KWH: f64 = 2_655_224.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AllocationSettings {
+    pub allocation: f64, // season allocation, gross inches over the field
+    pub area: f64,       // acres
+    pub lift: f64,       // pumping lift from the water level (ft)
+    pub pressure: f64,   // pressure at the pump (psi)
+    pub efficiency: f64, // overall pumping plant efficiency (0 - 1)
+    pub price: f64,      // $/kWh
+}
+
+impl Default for AllocationSettings {
+    fn default() -> Self {
+        // Same 125 acre circle as the default pivot, a deep western Kansas well on electric power
+        AllocationSettings { allocation: 14.0, area: 125.0, lift: 200.0, pressure: 30.0, efficiency: 0.6, price: 0.12 }
+    }
+}
+
+impl AllocationSettings {
+    pub fn acre_inches(&self, inches: f64) -> f64 {
+        inches * self.area
+    }
+
+    // Lift plus pressure as feet of water
+    pub fn head(&self) -> f64 {
+        self.lift + self.pressure * 2.31
+    }
+
+    // Energy to pump one acre-inch
+    pub fn kwh_per_acre_inch(&self) -> f64 {
+        ACRE_INCH_GALLONS * 8.34 * self.head() / FOOT_POUNDS_PER_KWH / self.efficiency.max(0.01)
+    }
+}
+
+pub fn acre_feet(acre_inches: f64) -> f64 {
+    acre_inches / 12.0
+}
+
+// Where a team stands against its allocation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Usage {
+    pub applied: f64,     // gross inches
+    pub acre_inches: f64,
+    pub remaining: f64,   // acre-inches left, negative once it's overdrawn
+    pub kwh: f64,
+    pub cost: f64,        // $
+}
+
+pub fn usage(settings: &AllocationSettings, applied: f64) -> Usage {
+    let acre_inches = settings.acre_inches(applied);
+    let kwh = acre_inches * settings.kwh_per_acre_inch();
+    Usage {
+        applied,
+        acre_inches,
+        remaining: settings.acre_inches(settings.allocation) - acre_inches,
+        kwh,
+        cost: kwh * settings.price,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pumping_energy() {
+        let settings = AllocationSettings::default();
+        // 200 ft + 30 psi is about 269 ft of head, 38 kWh an acre-inch at 60%
+        assert!((settings.head() - 269.3).abs() < 1e-9);
+        assert!((settings.kwh_per_acre_inch() - 38.3).abs() < 0.1);
+        assert!(AllocationSettings { efficiency: 0.0, ..settings }.kwh_per_acre_inch().is_finite());
+    }
+
+    #[test]
+    fn usage_against_the_allocation() {
+        let settings = AllocationSettings::default();
+        let usage = usage(&settings, 16.0);
+        assert_eq!(usage.acre_inches, 2000.0);
+        assert_eq!(usage.remaining, -250.0);
+        assert!((usage.cost - usage.kwh * 0.12).abs() < 1e-9);
+        assert_eq!(acre_feet(usage.acre_inches), 2000.0 / 12.0);
+    }
+}
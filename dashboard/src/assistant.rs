@@ -0,0 +1,144 @@
+// Equipment assistant: asks about the field and ranks the sensors for it
+
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::crop::Crop;
+use crate::equipment::{self, Decision, Questionnaire};
+use crate::soil;
+
+pub enum AssistantMessage {
+    Update(Questionnaire), // Any answer changed
+}
+
+pub struct Assistant {
+    questionnaire: Questionnaire,
+}
+
+impl Component for Assistant {
+    type Message = AssistantMessage;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Assistant {
+            questionnaire: Questionnaire::default(),
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            AssistantMessage::Update(questionnaire) => {
+                self.questionnaire = questionnaire;
+                true
+            },
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let questionnaire = self.questionnaire.clone();
+        let on_crop = ctx.link().callback(move |e: Event| {
+            let mut questionnaire = questionnaire.clone();
+            questionnaire.crop = Crop::from_name(&e.target_unchecked_into::<HtmlSelectElement>().value()).unwrap_or(questionnaire.crop);
+            AssistantMessage::Update(questionnaire)
+        });
+        let questionnaire = self.questionnaire.clone();
+        let on_plot = ctx.link().callback(move |e: Event| {
+            let mut questionnaire = questionnaire.clone();
+            questionnaire.plot_id = e.target_unchecked_into::<HtmlSelectElement>().value();
+            AssistantMessage::Update(questionnaire)
+        });
+        let questionnaire = self.questionnaire.clone();
+        let on_budget = ctx.link().callback(move |e: Event| {
+            let mut questionnaire = questionnaire.clone();
+            questionnaire.budget = e.target_unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(questionnaire.budget);
+            AssistantMessage::Update(questionnaire)
+        });
+
+        html! {
+            <>
+                <h2>{ "Equipment assistant" }</h2>
+                <ul>
+                    <li>
+                        <label>{ "Crop " }
+                            <select onchange={on_crop}>
+                                { for Crop::ALL.iter().map(|crop| html! {
+                                    <option selected={*crop == self.questionnaire.crop}>{ crop.name() }</option>
+                                }) }
+                            </select>
+                        </label>
+                    </li>
+                    <li>
+                        <label>{ "Soil " }
+                            <select onchange={on_plot}>
+                                { for soil::plot_ids().into_iter().map(|id| {
+                                    // Texture of the top sample is what people know their field by
+                                    let texture = soil::profile(&id).first().map(|layer| layer.texture.clone()).unwrap_or_default();
+                                    html! {
+                                        <option selected={id == self.questionnaire.plot_id} value={id.clone()}>{ format!("Plot {id} ({texture})") }</option>
+                                    }
+                                }) }
+                            </select>
+                        </label>
+                    </li>
+                    <li>
+                        <label>{ "Budget ($/season) " }
+                            <input type="number" min="0" step="100" value={self.questionnaire.budget.to_string()} onchange={on_budget} />
+                        </label>
+                    </li>
+                    <li>{ "Assumed costs ($/season per unit), put in real quotes when you have them:" }</li>
+                    { for equipment::CATALOG.iter().enumerate().map(|(index, equipment)| {
+                        let questionnaire = self.questionnaire.clone();
+                        let on_cost = ctx.link().callback(move |e: Event| {
+                            let mut questionnaire = questionnaire.clone();
+                            if let (Ok(cost), Some(old)) = (e.target_unchecked_into::<HtmlInputElement>().value().parse(), questionnaire.costs.get_mut(index)) {
+                                *old = cost;
+                            }
+                            AssistantMessage::Update(questionnaire)
+                        });
+                        html! {
+                            <li>
+                                <label>{ format!("{} ", equipment.name) }
+                                    <input type="number" min="0" step="50" value={self.questionnaire.cost(equipment).to_string()} onchange={on_cost} />
+                                </label>
+                            </li>
+                        }
+                    }) }
+                    <li>{ "Decisions it should help with:" }</li>
+                    { for Decision::ALL.iter().map(|&decision| {
+                        let questionnaire = self.questionnaire.clone();
+                        let on_toggle = ctx.link().callback(move |e: Event| {
+                            let mut questionnaire = questionnaire.clone();
+                            questionnaire.decisions.retain(|picked| *picked != decision);
+                            if e.target_unchecked_into::<HtmlInputElement>().checked() {
+                                questionnaire.decisions.push(decision);
+                            }
+                            AssistantMessage::Update(questionnaire)
+                        });
+                        html! {
+                            <li>
+                                <label>
+                                    <input type="checkbox" checked={self.questionnaire.decisions.contains(&decision)} onchange={on_toggle} />
+                                    { decision.name() }
+                                </label>
+                            </li>
+                        }
+                    }) }
+                </ul>
+                <hr />
+                <ol>
+                    { for equipment::rank(&self.questionnaire).iter().map(|ranking| html! {
+                        <li>
+                            <h3>{ format!("{} ({:.0}/100)", ranking.name(), ranking.score) }</h3>
+                            <ul>
+                                { for ranking.reasons.iter().map(|reason| html! { <li>{ reason }</li> }) }
+                                { for ranking.equipment.iter().flat_map(|equipment| equipment.tradeoffs.iter().map(move |tradeoff| html! {
+                                    <li>{ format!("{}: {}", equipment.name, tradeoff) }</li>
+                                })) }
+                            </ul>
+                        </li>
+                    }) }
+                </ol>
+            </>
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// Weekly rainfall draws from the command line, the same ones the dashboard makes
+//
+//     cargo run --bin rain_draws -- <seed> <season year> <count> [simulated_df.xlsx]
+//
+// Prints a CSV with one row per week (its first day) and one column per drawn season.
+
+#![allow(dead_code)] // the dashboard modules have more in them than this needs
+
+#[path = "../data.rs"]
+mod data;
+#[path = "../dates.rs"]
+mod dates;
+#[path = "../rain_generator.rs"]
+mod rain_generator;
+#[path = "../rainfall.rs"]
+mod rainfall;
+
+use calamine::{open_workbook_auto, Reader};
+
+const USAGE: &str = "usage: rain_draws <seed> <season year> <count> [simulated_df.xlsx]";
+
+fn main() {
+    if let Err(e) = run(&std::env::args().skip(1).collect::<Vec<String>>()) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let [seed, year, count, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let seed: u64 = seed.parse().map_err(|_| format!("Bad seed {seed}\n{USAGE}"))?;
+    let year: i64 = year.parse().map_err(|_| format!("Bad year {year}\n{USAGE}"))?;
+    let count: usize = count.parse().map_err(|_| format!("Bad count {count}\n{USAGE}"))?;
+    let path = rest.first().map(String::as_str).unwrap_or("../simulated_df.xlsx");
+
+    let mut workbook = open_workbook_auto(path).map_err(|e| format!("Failed to open {path}: {e}"))?;
+    let range = workbook.worksheet_range(rainfall::SHEET).map_err(|e| format!("Failed to read sheet {}: {e}", rainfall::SHEET))?;
+    let models: Vec<rain_generator::WeekModel> = rainfall::weeks(&range, year).iter().map(rain_generator::fit).collect();
+    if models.is_empty() {
+        return Err(format!("No weekly rainfall in {path}"));
+    }
+    let seasons = rain_generator::seasons(&models, seed, count);
+
+    let header: Vec<String> = (1..=count).map(|season| format!("season {season}")).collect();
+    println!("week,{}", header.join(","));
+    for (index, model) in models.iter().enumerate() {
+        let draws: Vec<String> = seasons.iter().map(|season| format!("{:.2}", season[index])).collect();
+        println!("{},{}", dates::iso(model.start), draws.join(","));
+    }
+    Ok(())
+}
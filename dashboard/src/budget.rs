@@ -0,0 +1,84 @@
+// Cumulative water budget from planting: what came in (rain and irrigation) against what the
+// crop used (ET), the difference is what the soil gained or lost
+//
+// Runoff and drainage aren't measured, so the storage change is really storage change plus
+// whatever ran off or drained. The probe's own storage change shows how much of it stayed.
+
+use crate::crop::StageSpan;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WaterDay {
+    pub day: f64,        // excel serial
+    pub rain: f64,       // inches
+    pub irrigation: f64, // inches
+    pub et: f64,         // inches
+}
+
+impl WaterDay {
+    // Water in less water used, what the soil (and runoff / drainage) made up
+    pub fn storage_change(&self) -> f64 {
+        self.rain + self.irrigation - self.et
+    }
+}
+
+// Running totals, every day's values added to everything before it
+pub fn cumulative(days: &[WaterDay]) -> Vec<WaterDay> {
+    days.iter()
+        .scan(WaterDay::default(), |total, day| {
+            *total = WaterDay {
+                day: day.day,
+                rain: total.rain + day.rain,
+                irrigation: total.irrigation + day.irrigation,
+                et: total.et + day.et,
+            };
+            Some(*total)
+        })
+        .collect()
+}
+
+// Everything in a run of days added up (day is the first of them)
+pub fn total(days: &[WaterDay]) -> WaterDay {
+    let day = days.first().map(|day| day.day).unwrap_or_default();
+    WaterDay { day, ..cumulative(days).last().copied().unwrap_or_default() }
+}
+
+// Totals for every crop stage the days reach into, with the days before the first stage up front
+pub fn by_stage(days: &[WaterDay], stages: &[StageSpan]) -> Vec<(String, WaterDay)> {
+    let mut rows = Vec::new();
+    if let Some(first) = stages.first() {
+        let before: Vec<WaterDay> = days.iter().filter(|day| day.day < first.start).copied().collect();
+        if !before.is_empty() {
+            rows.push((format!("Before {}", first.name), total(&before)));
+        }
+    }
+    for stage in stages {
+        let within: Vec<WaterDay> = days.iter().filter(|day| stage.start <= day.day && day.day < stage.end).copied().collect();
+        if !within.is_empty() {
+            rows.push((stage.name.to_string(), total(&within)));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals_by_stage() {
+        let days: Vec<WaterDay> = (0..10)
+            .map(|day| WaterDay { day: day as f64, rain: if day == 3 { 1.0 } else { 0.0 }, irrigation: if day == 6 { 0.85 } else { 0.0 }, et: 0.25 })
+            .collect();
+        let season = total(&days);
+        assert_eq!(season.day, 0.0);
+        assert!((season.storage_change() - (1.85 - 2.5)).abs() < 1e-9);
+        assert_eq!(cumulative(&days)[3].rain, 1.0);
+
+        let stages = [StageSpan { name: "V6", start: 2.0, end: 5.0 }, StageSpan { name: "VT", start: 5.0, end: 8.0 }, StageSpan { name: "R1", start: 20.0, end: 30.0 }];
+        let rows = by_stage(&days, &stages);
+        // The 8th and 9th fall between stages and R1 has no days at all
+        assert_eq!(rows.iter().map(|(name, _)| name.as_str()).collect::<Vec<&str>>(), vec!["Before V6", "V6", "VT"]);
+        assert_eq!(rows[1].1, WaterDay { day: 2.0, rain: 1.0, irrigation: 0.0, et: 0.75 });
+        assert_eq!(rows[2].1.irrigation, 0.85);
+    }
+}
@@ -0,0 +1,317 @@
+// Charts with more than one series on them (the single column plots live in main.rs)
+
+use std::ops::Range;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters_canvas::CanvasBackend;
+use web_sys::HtmlCanvasElement;
+
+use crate::crop::StageSpan;
+use crate::dates;
+
+// Height (px) of the crop stage strip under time series charts
+const STRIP_HEIGHT: u32 = 24;
+
+// One line on a chart, x values are excel dates
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub label: String,
+    pub color: RGBColor,
+    pub points: Vec<(f64, f64)>,
+}
+
+impl Series {
+    pub fn new(label: impl Into<String>, color: RGBColor, points: Vec<(f64, f64)>) -> Self {
+        Series { label: label.into(), color, points }
+    }
+}
+
+// Something that happened on a day, drawn as a labelled vertical line
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    pub day: f64,
+    pub label: String,
+    pub color: RGBColor,
+}
+
+impl Marker {
+    pub fn new(day: f64, label: impl Into<String>, color: RGBColor) -> Self {
+        Marker { day, label: label.into(), color }
+    }
+}
+
+// Space between two series of a chart, shaded one colour where `upper` is on top and another
+// where it has dropped under `lower` (the two series have to have the same days)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gap {
+    pub upper: usize, // index into the chart's series
+    pub lower: usize,
+    pub above: (String, RGBColor), // legend and colour while upper is on top
+    pub below: (String, RGBColor),
+}
+
+// Smallest box that fits every point, with a little room above and below
+fn bounds(series: &[Series]) -> (Range<f64>, Range<f64>) {
+    let points = || series.iter().flat_map(|series| series.points.iter());
+    let min_x = points().map(|p| p.0).fold(f64::MAX, f64::min);
+    let max_x = points().map(|p| p.0).fold(f64::MIN, f64::max);
+    let min_y = points().map(|p| p.1).fold(f64::MAX, f64::min);
+    let max_y = points().map(|p| p.1).fold(f64::MIN, f64::max);
+
+    if min_x > max_x {
+        return (0.0..1.0, 0.0..1.0); // nothing to draw
+    }
+    let pad = ((max_y - min_y) * 0.05).max(0.1);
+    (min_x..max_x.max(min_x + 1.0), min_y - pad..max_y + pad)
+}
+
+// Take the bottom of the area for the stage strip (when there are stages to show)
+pub fn split_for_stages<DB: DrawingBackend>(area: DrawingArea<DB, Shift>, stages: &[StageSpan]) -> (DrawingArea<DB, Shift>, Option<DrawingArea<DB, Shift>>) {
+    let height = area.dim_in_pixel().1;
+    if stages.is_empty() || height <= STRIP_HEIGHT * 4 {
+        return (area, None);
+    }
+    let (chart, strip) = area.split_vertically(height - STRIP_HEIGHT);
+    (chart, Some(strip))
+}
+
+// Crop stages as coloured blocks lined up with the chart above
+//
+// `y_label_area` has to be the chart's so the x axes line up.
+pub fn draw_stage_strip<DB: DrawingBackend>(area: &DrawingArea<DB, Shift>, x_range: Range<f64>, y_label_area: u32, stages: &[StageSpan]) {
+    let mut strip = ChartBuilder::on(area)
+        .margin_left(5)
+        .margin_right(5)
+        .y_label_area_size(y_label_area)
+        .build_cartesian_2d(x_range.clone(), 0.0..1.0).unwrap();
+
+    for (index, stage) in stages.iter().enumerate() {
+        let start = stage.start.max(x_range.start);
+        let end = stage.end.min(x_range.end);
+        if start >= end {
+            continue;
+        }
+        let color = if index % 2 == 0 { RGBColor(120, 170, 90) } else { RGBColor(170, 200, 120) };
+        strip.draw_series(std::iter::once(Rectangle::new([(start, 0.0), (end, 1.0)], color.filled()))).unwrap();
+        strip.draw_series(std::iter::once(Text::new(stage.name, (start, 0.8), ("sans-serif", 12).into_font()))).unwrap();
+    }
+}
+
+// Draw every series as a line on the same axes with a legend, markers on top and crop
+// stages underneath
+pub fn draw_lines(canvas: HtmlCanvasElement, caption: &str, series: &[Series], markers: &[Marker], stages: &[StageSpan]) {
+    draw_lines_with_gap(canvas, caption, series, None, markers, stages);
+}
+
+// Same as draw_lines with the gap between two of the series shaded under the lines
+pub fn draw_lines_with_gap(canvas: HtmlCanvasElement, caption: &str, series: &[Series], gap: Option<&Gap>, markers: &[Marker], stages: &[StageSpan]) {
+    let backend = CanvasBackend::with_canvas_object(canvas).unwrap();
+    let drawing_area = backend.into_drawing_area();
+    drawing_area.fill(&RGBColor(200, 200, 200)).unwrap(); // Same light gray as the single plots
+    let (chart_area, strip_area) = split_for_stages(drawing_area.clone(), stages);
+
+    let (x_range, y_range) = bounds(series);
+    if let Some(strip_area) = &strip_area {
+        draw_stage_strip(strip_area, x_range.clone(), 40, stages);
+    }
+    let mut chart = ChartBuilder::on(&chart_area)
+        .caption(caption, ("sans-serif", 14).into_font())
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(40)
+        .build_cartesian_2d(x_range.clone(), y_range.clone()).unwrap();
+
+    chart.configure_mesh()
+        .x_label_formatter(&|x| dates::label(*x))
+        .draw().unwrap();
+
+    for marker in markers.iter().filter(|marker| x_range.contains(&marker.day)) {
+        chart.draw_series(std::iter::once(PathElement::new(vec![(marker.day, y_range.start), (marker.day, y_range.end)], marker.color.stroke_width(1)))).unwrap();
+        chart.draw_series(std::iter::once(Text::new(marker.label.clone(), (marker.day, y_range.end), ("sans-serif", 10).into_font().color(&marker.color)))).unwrap();
+    }
+
+    if let Some(gap) = gap {
+        // One four sided piece per day, coloured by which side is on top
+        let pieces: Vec<(bool, Vec<(f64, f64)>)> = series[gap.upper].points.windows(2)
+            .zip(series[gap.lower].points.windows(2))
+            .map(|(upper, lower)| (upper[0].1 + upper[1].1 >= lower[0].1 + lower[1].1, vec![upper[0], upper[1], lower[1], lower[0]]))
+            .collect();
+        for (on_top, (label, color)) in [(true, &gap.above), (false, &gap.below)] {
+            let color = *color;
+            chart.draw_series(pieces.iter()
+                .filter(|(above, _)| *above == on_top)
+                .map(|(_, corners)| Polygon::new(corners.clone(), color.mix(0.4).filled()))).unwrap()
+                .label(label.clone())
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], color.mix(0.4).filled()));
+        }
+    }
+
+    for line in series {
+        let color = line.color;
+        chart.draw_series(LineSeries::new(line.points.clone(), color.stroke_width(2))).unwrap()
+            .label(line.label.clone())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw().unwrap();
+
+    drawing_area.present().unwrap();
+}
+
+// Series stacked on top of each other as filled areas, every series has to have the same days
+// (the first one sits on zero)
+pub fn draw_stacked(canvas: HtmlCanvasElement, caption: &str, layers: &[Series], markers: &[Marker], stages: &[StageSpan]) {
+    let backend = CanvasBackend::with_canvas_object(canvas).unwrap();
+    let drawing_area = backend.into_drawing_area();
+    drawing_area.fill(&RGBColor(200, 200, 200)).unwrap();
+    let (chart_area, strip_area) = split_for_stages(drawing_area.clone(), stages);
+
+    // Top edge of every layer
+    let mut tops: Vec<Series> = Vec::new();
+    for layer in layers {
+        let points = layer.points.iter().enumerate()
+            .map(|(index, &(day, value))| (day, value + tops.last().map(|below| below.points[index].1).unwrap_or(0.0)))
+            .collect();
+        tops.push(Series::new(layer.label.clone(), layer.color, points));
+    }
+
+    let (x_range, y_range) = bounds(&tops);
+    let y_range = 0.0..y_range.end;
+    if let Some(strip_area) = &strip_area {
+        draw_stage_strip(strip_area, x_range.clone(), 40, stages);
+    }
+    let mut chart = ChartBuilder::on(&chart_area)
+        .caption(caption, ("sans-serif", 14).into_font())
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(40)
+        .build_cartesian_2d(x_range.clone(), y_range.clone()).unwrap();
+
+    chart.configure_mesh()
+        .x_label_formatter(&|x| dates::label(*x))
+        .draw().unwrap();
+
+    for (index, top) in tops.iter().enumerate() {
+        let color = top.color;
+        let bottom: Vec<(f64, f64)> = match index {
+            0 => top.points.iter().map(|&(day, _)| (day, 0.0)).collect(),
+            _ => tops[index - 1].points.clone(),
+        };
+        let outline: Vec<(f64, f64)> = top.points.iter().copied().chain(bottom.into_iter().rev()).collect();
+        chart.draw_series(std::iter::once(Polygon::new(outline, color.mix(0.6).filled()))).unwrap()
+            .label(top.label.clone())
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], color.mix(0.6).filled()));
+        chart.draw_series(LineSeries::new(top.points.clone(), color.stroke_width(1))).unwrap();
+    }
+
+    for marker in markers.iter().filter(|marker| x_range.contains(&marker.day)) {
+        chart.draw_series(std::iter::once(PathElement::new(vec![(marker.day, y_range.start), (marker.day, y_range.end)], marker.color.stroke_width(1)))).unwrap();
+        chart.draw_series(std::iter::once(Text::new(marker.label.clone(), (marker.day, y_range.end), ("sans-serif", 10).into_font().color(&marker.color)))).unwrap();
+    }
+
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw().unwrap();
+
+    drawing_area.present().unwrap();
+}
+
+// Bars side by side for every group, x of every bar / marker point is the group index
+//
+// Only bars / markers with a label go in the legend, so long runs of bars can share one entry.
+pub fn draw_grouped_bars(canvas: HtmlCanvasElement, caption: &str, groups: &[String], bars: &[Series], markers: &[Series]) {
+    let backend = CanvasBackend::with_canvas_object(canvas).unwrap();
+    let drawing_area = backend.into_drawing_area();
+    drawing_area.fill(&RGBColor(200, 200, 200)).unwrap();
+
+    let max_y = bars.iter().chain(markers).flat_map(|series| series.points.iter().map(|p| p.1)).fold(0.0, f64::max);
+    let mut chart = ChartBuilder::on(&drawing_area)
+        .caption(caption, ("sans-serif", 14).into_font())
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(40)
+        .build_cartesian_2d(-0.5..groups.len() as f64 - 0.5, 0.0..max_y * 1.05 + 0.1).unwrap();
+
+    // Groups sit on whole numbers, anything between them has no label
+    chart.configure_mesh()
+        .disable_x_mesh()
+        .x_labels(groups.len())
+        .x_label_formatter(&|x| {
+            let index = x.round();
+            if (x - index).abs() < 1e-6 && index >= 0.0 { groups.get(index as usize).cloned().unwrap_or_default() } else { String::new() }
+        })
+        .x_label_style(("sans-serif", 10).into_font())
+        .draw().unwrap();
+
+    let width = 0.8 / bars.len().max(1) as f64;
+    for (index, bar) in bars.iter().enumerate() {
+        let color = bar.color;
+        let offset = -0.4 + index as f64 * width;
+        let drawn = chart.draw_series(bar.points.iter().map(|&(group, value)| {
+            Rectangle::new([(group + offset, 0.0), (group + offset + width, value)], color.filled())
+        })).unwrap();
+        if !bar.label.is_empty() {
+            drawn.label(bar.label.clone())
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+        }
+    }
+    for marker in markers {
+        let color = marker.color;
+        let drawn = chart.draw_series(marker.points.iter().map(|&point| Circle::new(point, 5, color.filled()))).unwrap();
+        if !marker.label.is_empty() {
+            drawn.label(marker.label.clone())
+                .legend(move |(x, y)| Circle::new((x + 5, y), 5, color.filled()));
+        }
+    }
+
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw().unwrap();
+
+    drawing_area.present().unwrap();
+}
+
+// Depth profiles, x of every point is the value and y the depth in inches (drawn going down)
+pub fn draw_profile(canvas: HtmlCanvasElement, caption: &str, x_label: &str, series: &[Series]) {
+    let backend = CanvasBackend::with_canvas_object(canvas).unwrap();
+    let drawing_area = backend.into_drawing_area();
+    drawing_area.fill(&RGBColor(200, 200, 200)).unwrap();
+
+    // Depth goes in negative so deeper is lower down
+    let flipped: Vec<Series> = series.iter()
+        .map(|line| Series::new(line.label.clone(), line.color, line.points.iter().map(|&(x, depth)| (x, -depth)).collect()))
+        .collect();
+    let (x_range, y_range) = bounds(&flipped);
+    let mut chart = ChartBuilder::on(&drawing_area)
+        .caption(caption, ("sans-serif", 14).into_font())
+        .margin(5)
+        .x_label_area_size(30)
+        .y_label_area_size(40)
+        .build_cartesian_2d(x_range, y_range.start.min(-1.0)..0.0).unwrap();
+
+    chart.configure_mesh()
+        .x_desc(x_label)
+        .y_desc("Depth (in)")
+        .y_label_formatter(&|y| format!("{:.0}", -y))
+        .draw().unwrap();
+
+    for line in &flipped {
+        let color = line.color;
+        chart.draw_series(LineSeries::new(line.points.clone(), color.stroke_width(2)).point_size(3)).unwrap()
+            .label(line.label.clone())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw().unwrap();
+
+    drawing_area.present().unwrap();
+}
@@ -0,0 +1,199 @@
+// Crop parameters for the water balance, values from FAO-56 tables 11, 12 and 22
+// Growth stage GDD (F, 86/50 method, from planting) are the usual extension rules of thumb
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crop {
+    Corn,
+    Sorghum,
+}
+
+// A growth stage and the days it lasted (excel serials)
+#[derive(Clone, Debug, PartialEq)]
+pub struct StageSpan {
+    pub name: &'static str,
+    pub start: f64,
+    pub end: f64,
+}
+
+// Everything the water balance needs to know about a crop
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CropCoefficients {
+    pub kc_ini: f64,
+    pub kc_mid: f64,
+    pub kc_end: f64,
+    // Length in days of the initial, development, mid-season and late season stages
+    pub stage_days: [f64; 4],
+    // Rooting depth (inches) at planting and once the development stage is over
+    pub root_initial: f64,
+    pub root_max: f64,
+    // Fraction of the available water that can go before the crop is stressed (p)
+    pub depletion_fraction: f64,
+    // Canopy height at full cover (m)
+    pub height: f64,
+}
+
+impl Crop {
+    pub const ALL: [Crop; 2] = [Crop::Corn, Crop::Sorghum];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Crop::Corn => "Corn",
+            Crop::Sorghum => "Sorghum",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Crop> {
+        Crop::ALL.into_iter().find(|crop| crop.name() == name)
+    }
+
+    // Growth stages and the accumulated GDD each one starts at
+    pub fn stages(&self) -> &'static [(&'static str, f64)] {
+        match self {
+            // For a ~2700 GDD hybrid
+            Crop::Corn => &[
+                ("VE", 120.0),
+                ("V6", 475.0),
+                ("V10", 740.0),
+                ("VT", 1350.0),
+                ("R1", 1400.0),
+                ("R2", 1660.0),
+                ("R3", 1925.0),
+                ("R4", 2190.0),
+                ("R5", 2450.0),
+                ("R6", 2700.0),
+            ],
+            // Vanderlip stages for a medium maturity hybrid
+            Crop::Sorghum => &[
+                ("Emergence", 150.0),
+                ("3 leaf", 350.0),
+                ("5 leaf", 550.0),
+                ("GPD", 900.0),
+                ("Flag leaf", 1150.0),
+                ("Boot", 1300.0),
+                ("Half bloom", 1500.0),
+                ("Soft dough", 2000.0),
+                ("Hard dough", 2400.0),
+                ("Maturity", 2800.0),
+            ],
+        }
+    }
+
+    // Lay the stages out on the calendar from accumulated GDD (day, total)
+    pub fn stage_timeline(&self, accumulated: &[(f64, f64)]) -> Vec<StageSpan> {
+        let Some(&(last, _)) = accumulated.last() else {
+            return Vec::new();
+        };
+        let mut spans: Vec<StageSpan> = Vec::new();
+        for &(name, gdd) in self.stages() {
+            let Some(&(start, _)) = accumulated.iter().find(|(_, total)| *total >= gdd) else { break };
+            // Several stages can be passed on the same day (or before the data starts), keep the latest
+            if let Some(previous) = spans.last_mut() {
+                if previous.start == start {
+                    spans.pop();
+                } else {
+                    previous.end = start;
+                }
+            }
+            spans.push(StageSpan { name, start, end: last + 1.0 });
+        }
+        spans
+    }
+
+    // Max air temperature (F) from which the crop is heat stressed
+    pub fn heat_stress(&self) -> f64 {
+        match self {
+            Crop::Corn => 95.0,
+            Crop::Sorghum => 100.0,
+        }
+    }
+
+    pub fn coefficients(&self) -> CropCoefficients {
+        match self {
+            Crop::Corn => CropCoefficients {
+                kc_ini: 0.3,
+                kc_mid: 1.2,
+                kc_end: 0.6,
+                stage_days: [30.0, 40.0, 50.0, 30.0],
+                root_initial: 6.0,
+                root_max: 48.0,
+                depletion_fraction: 0.55,
+                height: 2.0,
+            },
+            Crop::Sorghum => CropCoefficients {
+                kc_ini: 0.3,
+                kc_mid: 1.1,
+                kc_end: 0.55,
+                stage_days: [20.0, 35.0, 40.0, 30.0],
+                root_initial: 6.0,
+                root_max: 48.0,
+                depletion_fraction: 0.55,
+                height: 1.5,
+            },
+        }
+    }
+}
+
+impl CropCoefficients {
+    // FAO-56 crop coefficient curve: flat, ramp up, flat, ramp down
+    pub fn kc(&self, days_after_planting: f64) -> f64 {
+        let [ini, dev, mid, late] = self.stage_days;
+        let day = days_after_planting.max(0.0);
+        if day < ini {
+            self.kc_ini
+        } else if day < ini + dev {
+            self.kc_ini + (self.kc_mid - self.kc_ini) * (day - ini) / dev
+        } else if day < ini + dev + mid {
+            self.kc_mid
+        } else if day < ini + dev + mid + late {
+            self.kc_mid + (self.kc_end - self.kc_mid) * (day - ini - dev - mid) / late
+        } else {
+            self.kc_end
+        }
+    }
+
+    // Roots grow linearly until the end of the development stage
+    pub fn root_depth(&self, days_after_planting: f64) -> f64 {
+        let growing = self.stage_days[0] + self.stage_days[1];
+        let fraction = (days_after_planting / growing).clamp(0.0, 1.0);
+        self.root_initial + (self.root_max - self.root_initial) * fraction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kc_curve_follows_the_stages() {
+        let corn = Crop::Corn.coefficients();
+        assert_eq!(corn.kc(-5.0), 0.3);
+        assert_eq!(corn.kc(10.0), 0.3);
+        assert!((corn.kc(50.0) - 0.75).abs() < 1e-9);
+        assert_eq!(corn.kc(100.0), 1.2);
+        assert!((corn.kc(135.0) - 0.9).abs() < 1e-9);
+        assert_eq!(corn.kc(200.0), 0.6);
+    }
+
+    #[test]
+    fn roots_stop_growing_after_development() {
+        let corn = Crop::Corn.coefficients();
+        assert_eq!(corn.root_depth(0.0), 6.0);
+        assert_eq!(corn.root_depth(35.0), 27.0);
+        assert_eq!(corn.root_depth(120.0), 48.0);
+    }
+
+    #[test]
+    fn stages_on_the_calendar() {
+        // 100 GDD a day for a month from day 100
+        let accumulated: Vec<(f64, f64)> = (0..30).map(|day| (100.0 + day as f64, 100.0 * (day + 1) as f64)).collect();
+        let stages = Crop::Corn.stage_timeline(&accumulated);
+        assert_eq!(stages[0], StageSpan { name: "VE", start: 101.0, end: 104.0 });
+        assert_eq!(stages[1].name, "V6");
+        // VT and R1 are passed the same day, only R1 is kept
+        assert!(stages.iter().all(|stage| stage.name != "VT"));
+        assert!(stages.contains(&StageSpan { name: "R1", start: 113.0, end: 116.0 }));
+        assert!(stages.windows(2).all(|pair| pair[0].end == pair[1].start));
+        assert_eq!(stages.last(), Some(&StageSpan { name: "R6", start: 126.0, end: 130.0 }));
+        assert!(Crop::Corn.stage_timeline(&[]).is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek};
+
+use calamine::{open_workbook_auto_from_rs, Data, Range, Reader, Sheets}; // parse xlsx files
+
+// Where the dashboard (and the data it reads) is hosted
+pub const BASE_URL: &str = "https://k-state-drake-morgan.github.io/TAPS-2024-Hackathon/";
+
+pub const AQUASPY_FILE: &str = "data/24 KSU TAPS AquaSpy.xlsx";
+pub const ARABLE_FILE: &str = "data/24 KSU TAPS Arable.xlsx";
+
+// Teams that have a sheet in the Arable workbook
+pub const ARABLE_TEAMS: [u32; 12] = [2, 4, 5, 13, 15, 16, 18, 21, 22, 26, 27, 28];
+
+// Teams that have a sheet in the AquaSpy workbook
+pub const AQUASPY_TEAMS: [u32; 2] = [12, 16];
+
+// Arable used when any weather will do (they're all in the same field)
+pub const WEATHER_TEAM: u32 = 16;
+
+// A team's own Arable if it has one, the field's otherwise
+pub fn arable_team(team: u32) -> u32 {
+    if ARABLE_TEAMS.contains(&team) { team } else { WEATHER_TEAM }
+}
+
+// Every workbook names its sheets the same way
+pub fn sheet_name(team: u32) -> String {
+    format!("Team #{team} Data")
+}
+
+// Team a sheet belongs to, from its name
+pub fn sheet_team(sheet: &str) -> Option<u32> {
+    sheet.strip_prefix("Team #")?.strip_suffix(" Data")?.parse().ok()
+}
+
+// Every team with a sheet in either workbook, in order
+pub fn teams() -> Vec<u32> {
+    let mut teams: Vec<u32> = ARABLE_TEAMS.iter().chain(AQUASPY_TEAMS.iter()).copied().collect();
+    teams.sort();
+    teams.dedup();
+    teams
+}
+
+// Column layout of the Arable sheets (one row per day)
+//
+// NOTE: the evapotranspiration headers say (mm) but the values are clearly inches
+// (0.3 a day in July), same for precipitation, and temperatures are in F.
+pub mod arable {
+    pub const TIMESTAMP: usize = 0;
+    pub const FIELD_ET: usize = 2;
+    pub const CANOPY_ET: usize = 3;
+    pub const GDD: usize = 4;
+    pub const ACCUMULATED_GDD: usize = 5;
+    pub const NDVI: usize = 6;
+    pub const RH_AT_MAX_TEMP: usize = 8;
+    pub const RH_AT_MIN_TEMP: usize = 9;
+    pub const SHORTWAVE: usize = 10; // MJ/m2/day
+    pub const MAX_TEMP: usize = 11;
+    pub const MIN_TEMP: usize = 13;
+    pub const PRECIPITATION: usize = 17;
+    pub const CROP_COEFFICIENT: usize = 22;
+    pub const WIND_SPEED: usize = 28; // mph
+}
+
+// Column layout of the AquaSpy sheets, each block has one column per depth
+//
+// NOTE: moisture is volumetric %, temperature is F like the rest of the workbooks and EC is
+// left in whatever unit the probe reports.
+pub mod aquaspy {
+    pub const TIMESTAMP: usize = 0;
+    pub const MOISTURE: usize = 2;
+    pub const EC: usize = 14;
+    pub const TEMPERATURE: usize = 26;
+    // Depth of every sensor on the probe in inches, in column order
+    pub const DEPTHS: [f64; 12] = [4.0, 8.0, 12.0, 16.0, 20.0, 24.0, 28.0, 32.0, 36.0, 40.0, 44.0, 48.0];
+}
+
+// Download one of the workbooks
+async fn fetch_workbook(relative_url: &str) -> Result<Sheets<impl Read + Seek>, String> {
+    let response = reqwest::get(BASE_URL.to_string() + relative_url)
+        .await
+        .map_err(|e| format!("Failed to fetch data: {}", e))?;
+
+    let bytes = response.bytes()
+        .await
+        .map_err(|e| format!("Failed to read response bytes: {}", e))?;
+
+    open_workbook_auto_from_rs(Cursor::new(bytes))
+        .map_err(|e| format!("Failed to open workbook: {}", e))
+}
+
+// Download one of the workbooks and pull a single sheet out of it
+pub async fn fetch_sheet(relative_url: &str, sheet: &str) -> Result<Range<Data>, String> {
+    fetch_workbook(relative_url).await?
+        .worksheet_range(sheet)
+        .map_err(|e| format!("Failed to read sheet {}: {}", sheet, e))
+}
+
+// Download one of the workbooks and pull every sheet out of it by name, for going through all
+// the teams without fetching it again for each
+pub async fn fetch_sheets(relative_url: &str) -> Result<HashMap<String, Range<Data>>, String> {
+    Ok(fetch_workbook(relative_url).await?.worksheets().into_iter().collect())
+}
+
+// Numeric value of a cell, dates come back as their excel serial
+pub fn cell_f64(cell: &Data) -> Option<f64> {
+    match cell {
+        Data::Int(x) => Some(*x as f64),
+        Data::Float(x) => Some(*x),
+        Data::DateTime(excel_date_time) => Some(excel_date_time.as_f64()),
+        // Anything else ("N/A", headers, blanks...) isn't a reading
+        _ => None,
+    }
+}
+
+// Pull the timestamp and the requested columns out of every row that has a timestamp
+//
+// Missing readings stay as None so callers can decide how to fill them.
+pub fn table(range: &Range<Data>, timestamp: usize, columns: &[usize]) -> Vec<(f64, Vec<Option<f64>>)> {
+    range.rows()
+        .filter_map(|row| {
+            let time = row.get(timestamp).and_then(cell_f64)?;
+            let values = columns.iter().map(|&column| row.get(column).and_then(cell_f64)).collect();
+            Some((time, values))
+        })
+        .collect()
+}
+
+// Average every reading that falls on the same day, the key becomes midnight of that day
+// (rows are expected in time order, which is how the sheets come)
+pub fn daily_mean(rows: &[(f64, Vec<Option<f64>>)]) -> Vec<(f64, Vec<Option<f64>>)> {
+    let mut days: Vec<(f64, Vec<(f64, usize)>)> = Vec::new();
+
+    for (time, values) in rows {
+        let day = time.floor();
+        if days.last().map(|(last, _)| *last != day).unwrap_or(true) {
+            days.push((day, vec![(0.0, 0); values.len()]));
+        }
+        let (_, sums) = days.last_mut().unwrap();
+        for (sum, value) in sums.iter_mut().zip(values) {
+            if let Some(value) = value {
+                sum.0 += value;
+                sum.1 += 1;
+            }
+        }
+    }
+
+    days.into_iter()
+        .map(|(day, sums)| (day, sums.into_iter().map(|(sum, count)| (count > 0).then(|| sum / count as f64)).collect()))
+        .collect()
+}
@@ -0,0 +1,87 @@
+// Helpers for the excel date serials that every sheet uses as its x value
+//
+// Excel counts days from 1899-12-30 (thanks to the 1900 leap year bug), so a whole
+// number is midnight of that day and the fraction is the time of day.
+
+// Days between the excel epoch and the unix epoch
+const UNIX_EPOCH: i64 = 25569;
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+// Turn an excel serial into a (year, month, day) calendar date
+pub fn to_ymd(serial: f64) -> (i64, u32, u32) {
+    // civil_from_days from http://howardhinnant.github.io/date_algorithms.html
+    let z = serial.floor() as i64 - UNIX_EPOCH + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// Turn a calendar date into an excel serial (midnight of that day)
+pub fn from_ymd(year: i64, month: u32, day: u32) -> f64 {
+    // days_from_civil from the same place as above
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    (era * 146097 + doe - 719468 + UNIX_EPOCH) as f64
+}
+
+// Parse the "YYYY-MM-DD" value an <input type="date"> gives back
+pub fn parse_iso(text: &str) -> Option<f64> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(from_ymd(year, month, day))
+}
+
+// Format an excel serial as "YYYY-MM-DD" (what <input type="date"> wants)
+pub fn iso(serial: f64) -> String {
+    let (year, month, day) = to_ymd(serial);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// Short "Jun 04" style label used on chart axes
+pub fn label(serial: f64) -> String {
+    let (_, month, day) = to_ymd(serial);
+    format!("{} {:02}", MONTHS[(month - 1) as usize], day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_round_trip() {
+        // Both sides of leap days, the 1900 bug and a century that isn't a leap year
+        for (year, month, day) in [(1900, 3, 1), (1970, 1, 1), (2000, 2, 29), (2024, 2, 29), (2024, 12, 31), (2100, 3, 1)] {
+            assert_eq!(to_ymd(from_ymd(year, month, day)), (year, month, day));
+        }
+        for serial in 40_000..50_000 {
+            let (year, month, day) = to_ymd(serial as f64);
+            assert_eq!(from_ymd(year, month, day), serial as f64);
+        }
+    }
+
+    #[test]
+    fn known_serials() {
+        assert_eq!(from_ymd(1970, 1, 1), UNIX_EPOCH as f64);
+        assert_eq!(from_ymd(2024, 5, 1), 45413.0);
+        assert_eq!(to_ymd(45413.75), (2024, 5, 1));
+        assert_eq!(iso(45413.0), "2024-05-01");
+        assert_eq!(parse_iso("2024-05-01"), Some(45413.0));
+        assert_eq!(parse_iso("2024-13-01"), None);
+    }
+}
@@ -0,0 +1,53 @@
+// Crop water use from how fast the AquaSpy profile dries out
+//
+// Between wetting events nothing comes in, so whatever the whole profile loses from one
+// midnight to the next is what the crop (and the soil surface) used. Event days are left out,
+// and so are the days straight after while the water is still draining through.
+
+use crate::events::{self, WettingEvent};
+
+// Days after an event's rise has settled that are still left out
+const DRAINING: f64 = 1.0;
+
+// Whether a day (excel serial) is too close to an event to read use from
+pub fn disturbed(events: &[WettingEvent], day: f64) -> bool {
+    events.iter().any(|event| event.start.floor() <= day && day <= event.end.floor() + DRAINING)
+}
+
+// Inches used every day that isn't disturbed (readings in time order)
+pub fn daily_use(moisture: &[(f64, Vec<Option<f64>>)], events: &[WettingEvent]) -> Vec<(f64, f64)> {
+    // Profile storage at the first complete reading of every day
+    let mut mornings: Vec<(f64, f64)> = Vec::new();
+    for (time, readings) in moisture {
+        let Some(storage) = events::storage(readings) else { continue };
+        if mornings.last().map(|(day, _)| *day != time.floor()).unwrap_or(true) {
+            mornings.push((time.floor(), storage));
+        }
+    }
+
+    mornings.windows(2)
+        .filter(|pair| pair[1].0 - pair[0].0 == 1.0 && !disturbed(events, pair[0].0))
+        .map(|pair| (pair[0].0, pair[0].1 - pair[1].1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::aquaspy;
+    use crate::events::EventKind;
+
+    #[test]
+    fn use_between_events() {
+        // Every sensor loses half a point a day, 0.24 in out of the whole profile
+        let moisture: Vec<(f64, Vec<Option<f64>>)> = (0..6 * 24)
+            .map(|hour| (45474.0 + hour as f64 / 24.0, vec![Some(30.0 - 0.5 * hour as f64 / 24.0); aquaspy::DEPTHS.len()]))
+            .collect();
+        let event = WettingEvent { start: 45476.4, peak: 45476.5, end: 45476.7, rise: 3.0, applied: 0.5, rain: 0.0, kind: EventKind::Rain };
+        let days = daily_use(&moisture, &[event]);
+        // The event day and the one after are left out, the last day has no next morning
+        assert_eq!(days.iter().map(|(day, _)| *day).collect::<Vec<f64>>(), vec![45474.0, 45475.0, 45478.0]);
+        assert!(days.iter().all(|(_, used)| (used - 0.24).abs() < 1e-9));
+        assert!(disturbed(&[event], 45477.0) && !disturbed(&[event], 45478.0));
+    }
+}
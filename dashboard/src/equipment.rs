@@ -0,0 +1,299 @@
+// Equipment catalog and the scoring behind the equipment assistant
+//
+// What each sensor can do comes from the columns its workbook actually has, not from the
+// vendor brochures.
+
+use crate::crop::Crop;
+use crate::soil::{self, SoilLayer};
+
+// Something a sensor measures that a decision can use
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Capability {
+    SoilMoisture,
+    MoistureProfile, // moisture at many depths through the root zone
+    AvailableWater,  // moisture already converted to inches of available water
+    SoilEc,
+    SoilTemperature,
+    Weather,
+    Evapotranspiration,
+    Rainfall,
+    CanopyReflectance, // NDVI / chlorophyll
+}
+
+impl Capability {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Capability::SoilMoisture => "soil moisture",
+            Capability::MoistureProfile => "a multi-depth moisture profile",
+            Capability::AvailableWater => "available water in inches",
+            Capability::SoilEc => "soil EC",
+            Capability::SoilTemperature => "soil temperature",
+            Capability::Weather => "weather",
+            Capability::Evapotranspiration => "evapotranspiration",
+            Capability::Rainfall => "rainfall",
+            Capability::CanopyReflectance => "NDVI / chlorophyll",
+        }
+    }
+}
+
+pub struct Equipment {
+    pub name: &'static str,
+    pub capabilities: &'static [Capability],
+    pub deepest: Option<f64>, // deepest soil reading in inches, if it reads the soil
+    // Starting guess at one unit for a season (hardware share + subscription), the assistant
+    // shows it as an assumption to replace with a real quote
+    pub cost: f64,
+    pub tradeoffs: &'static [&'static str],
+}
+
+pub const CATALOG: [Equipment; 3] = [
+    Equipment {
+        name: "AquaSpy",
+        // Moisture, EC and temperature every 4 inches from 4 to 48
+        capabilities: &[Capability::SoilMoisture, Capability::MoistureProfile, Capability::SoilEc, Capability::SoilTemperature],
+        deepest: Some(48.0),
+        cost: 1200.0,
+        tradeoffs: &[
+            "Sees the whole root zone, so it shows how deep water goes and where the roots are pulling from.",
+            "Readings are relative, they need field capacity from the soil to turn into inches.",
+            "Nothing above ground, it can't tell you what tomorrow's demand will be.",
+        ],
+    },
+    Equipment {
+        name: "Arable",
+        // Daily ET, NDVI, chlorophyll, temperature, humidity, radiation, wind and rain
+        capabilities: &[Capability::Weather, Capability::Evapotranspiration, Capability::Rainfall, Capability::CanopyReflectance],
+        deepest: None,
+        cost: 900.0,
+        tradeoffs: &[
+            "Measures the demand side (ET, weather) and crop health in one unit.",
+            "Doesn't see the soil, the water balance has to be modelled from its ET and rain.",
+            "One unit per field covers the whole pivot, weather doesn't change much across it.",
+        ],
+    },
+    Equipment {
+        name: "GroGuru",
+        // Available water (in), absolute and relative moisture and temperature per sensor
+        capabilities: &[Capability::SoilMoisture, Capability::AvailableWater, Capability::SoilTemperature],
+        // The workbook numbers six sensors without saying how deep they are. Daily temperature
+        // swings fade from #1 to #6 and each holds about 1.2 in of available water, which
+        // fits six 6 in layers, so the deepest is taken to be 36 in.
+        deepest: Some(36.0),
+        cost: 700.0,
+        tradeoffs: &[
+            "Reports available water straight in inches, easy to act on.",
+            "Six sensors to about 36 in, a coarser picture of the profile than a full probe.",
+            "Buried and wireless, nothing to dodge with equipment.",
+        ],
+    },
+];
+
+// A decision the team wants the sensors to help with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Decision {
+    WhenToIrrigate,
+    HowMuchToIrrigate,
+    Fertility,
+    PlantingDate,
+    CropHealth,
+    HeatAndDemand,
+}
+
+impl Decision {
+    pub const ALL: [Decision; 6] = [
+        Decision::WhenToIrrigate,
+        Decision::HowMuchToIrrigate,
+        Decision::Fertility,
+        Decision::PlantingDate,
+        Decision::CropHealth,
+        Decision::HeatAndDemand,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Decision::WhenToIrrigate => "When to irrigate",
+            Decision::HowMuchToIrrigate => "How much to irrigate",
+            Decision::Fertility => "Fertility / salinity",
+            Decision::PlantingDate => "Planting date",
+            Decision::CropHealth => "Crop health",
+            Decision::HeatAndDemand => "Heat and water demand",
+        }
+    }
+
+    // What the decision needs, and how much each one counts
+    pub fn needs(&self) -> &'static [(Capability, f64)] {
+        match self {
+            Decision::WhenToIrrigate => &[(Capability::SoilMoisture, 1.0), (Capability::Evapotranspiration, 0.5), (Capability::Rainfall, 0.5)],
+            Decision::HowMuchToIrrigate => &[(Capability::MoistureProfile, 1.0), (Capability::AvailableWater, 0.5), (Capability::Evapotranspiration, 1.0)],
+            Decision::Fertility => &[(Capability::SoilEc, 1.0)],
+            Decision::PlantingDate => &[(Capability::SoilTemperature, 1.0), (Capability::Weather, 0.5)],
+            Decision::CropHealth => &[(Capability::CanopyReflectance, 1.0), (Capability::Weather, 0.25)],
+            Decision::HeatAndDemand => &[(Capability::Weather, 1.0), (Capability::Evapotranspiration, 0.5)],
+        }
+    }
+}
+
+// Answers to the questionnaire
+#[derive(Clone, Debug, PartialEq)]
+pub struct Questionnaire {
+    pub crop: Crop,
+    pub plot_id: String, // soil texture comes from this plot's samples
+    pub budget: f64,     // dollars for the season
+    pub decisions: Vec<Decision>,
+    pub costs: Vec<f64>, // $/season for each CATALOG entry, the catalog's guesses until changed
+}
+
+impl Questionnaire {
+    // What a unit is taken to cost
+    pub fn cost(&self, equipment: &Equipment) -> f64 {
+        CATALOG.iter()
+            .position(|other| other.name == equipment.name)
+            .and_then(|index| self.costs.get(index).copied())
+            .unwrap_or(equipment.cost)
+    }
+}
+
+impl Default for Questionnaire {
+    fn default() -> Self {
+        Questionnaire {
+            crop: Crop::Corn,
+            plot_id: soil::plot_ids().into_iter().next().unwrap_or_default(),
+            budget: 2000.0,
+            decisions: vec![Decision::WhenToIrrigate, Decision::HowMuchToIrrigate],
+            costs: CATALOG.iter().map(|equipment| equipment.cost).collect(),
+        }
+    }
+}
+
+// How well one set of equipment answers the questionnaire
+pub struct Ranking {
+    pub equipment: Vec<&'static Equipment>,
+    pub cost: f64,
+    pub score: f64, // 0 - 100
+    pub reasons: Vec<String>,
+}
+
+impl Ranking {
+    pub fn name(&self) -> String {
+        self.equipment.iter().map(|equipment| equipment.name).collect::<Vec<_>>().join(" + ")
+    }
+}
+
+// Soils that hold a lot of water need the deep readings to see it
+fn deep_storage(profile: &[SoilLayer], root_depth: f64) -> bool {
+    root_depth > 0.0 && soil::total_available_water(profile, root_depth) / root_depth > 0.15
+}
+
+// Clay makes bulk EC follow moisture more than salts
+fn clayey(profile: &[SoilLayer], root_depth: f64) -> bool {
+    profile.iter().filter(|layer| layer.top < root_depth).any(|layer| layer.clay >= 27.0)
+}
+
+fn score(equipment: Vec<&'static Equipment>, questionnaire: &Questionnaire, profile: &[SoilLayer]) -> Ranking {
+    let root_depth = questionnaire.crop.coefficients().root_max;
+    let has = |capability: Capability| equipment.iter().any(|equipment| equipment.capabilities.contains(&capability));
+    let cost: f64 = equipment.iter().map(|equipment| questionnaire.cost(equipment)).sum();
+    let mut reasons = Vec::new();
+
+    let mut earned = 0.0;
+    let mut possible = 0.0;
+    for decision in &questionnaire.decisions {
+        let mut missing = Vec::new();
+        for &(capability, weight) in decision.needs() {
+            // Deep soils make the profile matter more for how much to put on
+            let weight = if capability == Capability::MoistureProfile && deep_storage(profile, root_depth) { weight * 1.5 } else { weight };
+            possible += weight;
+            if has(capability) {
+                earned += weight;
+            } else {
+                missing.push(capability.name());
+            }
+        }
+        if missing.is_empty() {
+            reasons.push(format!("+ {}: covered.", decision.name()));
+        } else {
+            reasons.push(format!("- {}: missing {}.", decision.name(), missing.join(", ")));
+        }
+    }
+    let mut score = if possible > 0.0 { earned / possible * 100.0 } else { 0.0 };
+
+    let deepest = equipment.iter().filter_map(|equipment| equipment.deepest).fold(0.0, f64::max);
+    if deepest > 0.0 && deepest < root_depth {
+        reasons.push(format!("- Only reads down to {deepest} in, {} roots reach {root_depth} in.", questionnaire.crop.name().to_lowercase()));
+    } else if deepest >= root_depth {
+        reasons.push(format!("+ Reads the whole {root_depth} in {} root zone.", questionnaire.crop.name().to_lowercase()));
+    }
+    if has(Capability::MoistureProfile) && deep_storage(profile, root_depth) {
+        reasons.push(format!("+ Plot {} holds a lot of water deep down, the profile shows it.", questionnaire.plot_id));
+    }
+    if has(Capability::SoilEc) && clayey(profile, root_depth) && questionnaire.decisions.contains(&Decision::Fertility) {
+        reasons.push(format!("- Plot {} has clayey layers, EC will track moisture as much as nutrients.", questionnaire.plot_id));
+    }
+
+    if cost > questionnaire.budget {
+        // Over budget still ranks, just lower the further over it is
+        score *= questionnaire.budget / cost;
+        reasons.push(format!("- About ${:.0} for the season (assumed costs), ${:.0} over budget.", cost, cost - questionnaire.budget));
+    } else {
+        reasons.push(format!("+ About ${:.0} for the season (assumed costs), within budget.", cost));
+    }
+
+    Ranking { equipment, cost, score, reasons }
+}
+
+// Every combination of catalog equipment, best first
+pub fn rank(questionnaire: &Questionnaire) -> Vec<Ranking> {
+    let profile = soil::profile(&questionnaire.plot_id);
+    let mut rankings: Vec<Ranking> = (1..1u32 << CATALOG.len())
+        .map(|mask| {
+            let equipment = CATALOG.iter().enumerate().filter(|(index, _)| mask & (1 << index) != 0).map(|(_, equipment)| equipment).collect();
+            score(equipment, questionnaire, &profile)
+        })
+        .collect();
+    // Cheaper wins a tie
+    rankings.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.cost.total_cmp(&b.cost)));
+    rankings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(rankings: &'a [Ranking], name: &str) -> &'a Ranking {
+        rankings.iter().find(|ranking| ranking.name() == name).unwrap()
+    }
+
+    #[test]
+    fn best_first_and_full_coverage_on_top() {
+        let questionnaire = Questionnaire { budget: 1e6, ..Questionnaire::default() };
+        let rankings = rank(&questionnaire);
+        assert_eq!(rankings.len(), 7);
+        assert!(rankings.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        // Everything covers both irrigation decisions, the cheapest set that does wins the tie
+        assert_eq!(rankings[0].score, 100.0);
+        assert!(rankings.iter().filter(|ranking| ranking.score == 100.0).all(|ranking| ranking.cost >= rankings[0].cost));
+        assert!(find(&rankings, "Arable").score < find(&rankings, "AquaSpy + Arable").score);
+    }
+
+    #[test]
+    fn over_budget_scales_the_score() {
+        let rich = rank(&Questionnaire { budget: 1e6, ..Questionnaire::default() });
+        let full = find(&rich, "AquaSpy + Arable + GroGuru");
+        let poor = rank(&Questionnaire { budget: full.cost / 2.0, ..Questionnaire::default() });
+        assert!((find(&poor, "AquaSpy + Arable + GroGuru").score - full.score / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn typed_costs_replace_the_guesses() {
+        let questionnaire = Questionnaire { costs: vec![100.0, 200.0, 300.0], ..Questionnaire::default() };
+        assert_eq!(questionnaire.cost(&CATALOG[2]), 300.0);
+        assert_eq!(find(&rank(&questionnaire), "AquaSpy + Arable").cost, 300.0);
+    }
+
+    #[test]
+    fn shallow_probe_is_flagged() {
+        let rankings = rank(&Questionnaire::default());
+        let groguru = find(&rankings, "GroGuru");
+        assert!(groguru.reasons.iter().any(|reason| reason.starts_with("- Only reads down to 36 in")));
+    }
+}
@@ -0,0 +1,171 @@
+// Wetting events (rain or irrigation) found in the AquaSpy moisture readings
+//
+// Water arriving at the surface shows up as a quick rise at the shallow sensors. Nobody logs
+// when the pivot ran, so the rain gauge on the Arable is what tells the two apart.
+
+use crate::data::aquaspy;
+use crate::dates;
+
+// Sensors counted as shallow (4, 8 and 12 inches)
+const SHALLOW: usize = 3;
+// Rise in the shallow average (volumetric % points) that counts as water arriving
+const RISE: f64 = 1.5;
+// How far back (days) the rise is measured from
+const LOOKBACK: f64 = 0.5;
+// An event is over once the shallow sensors haven't gone any higher for this long (days)
+const SETTLE: f64 = 0.5;
+// Rain (inches) that has to fall around an event before it's put down to rain
+const MIN_RAIN: f64 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EventKind {
+    Rain,
+    Irrigation,
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Rain => "Rain",
+            EventKind::Irrigation => "Irrigation",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WettingEvent {
+    pub start: f64,   // excel serial (with time) of the last reading before the rise
+    pub peak: f64,    // when the shallow sensors topped out
+    pub end: f64,     // when they stopped rising
+    pub rise: f64,    // shallow average, volumetric % points
+    pub applied: f64, // inches the whole profile gained
+    pub rain: f64,    // inches the Arable caught on the days of the event
+    pub kind: EventKind,
+}
+
+impl WettingEvent {
+    pub fn label(&self) -> String {
+        format!("{} {:.2} in", self.kind.name(), self.applied)
+    }
+}
+
+// Thickness of soil every sensor stands for (inches)
+pub fn thickness() -> f64 {
+    aquaspy::DEPTHS[1] - aquaspy::DEPTHS[0]
+}
+
+// Water held in the probe's whole depth (inches), None if a sensor is missing
+pub fn storage(readings: &[Option<f64>]) -> Option<f64> {
+    readings.iter().map(|reading| reading.map(|moisture| moisture / 100.0 * thickness())).sum()
+}
+
+fn shallow(readings: &[Option<f64>]) -> Option<f64> {
+    let values: Vec<f64> = readings.iter().take(SHALLOW).flatten().copied().collect();
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+// Rain on the days from `start` to `end` (daily Arable totals, excel serial of the day)
+fn rain_between(rain: &[(f64, f64)], start: f64, end: f64) -> f64 {
+    rain.iter().filter(|(day, _)| start.floor() <= *day && *day <= end.floor()).fold(0.0, |total, (_, amount)| total + amount)
+}
+
+// Every wetting event in a run of readings (in time order, not averaged by day)
+pub fn detect(moisture: &[(f64, Vec<Option<f64>>)], rain: &[(f64, f64)]) -> Vec<WettingEvent> {
+    let shallow: Vec<(f64, f64)> = moisture.iter().filter_map(|(time, readings)| Some((*time, shallow(readings)?))).collect();
+    let stored: Vec<(f64, f64)> = moisture.iter().filter_map(|(time, readings)| Some((*time, storage(readings)?))).collect();
+    let stored_at = |time: f64| stored.iter().find(|(other, _)| *other >= time).map(|(_, storage)| *storage);
+
+    let mut events = Vec::new();
+    let mut index = 0;
+    while index < shallow.len() {
+        let (time, value) = shallow[index];
+        // Lowest reading in the lookback window is where the rise started from
+        let Some(&(start, base)) = shallow[..index].iter()
+            .filter(|(other, _)| time - other <= LOOKBACK)
+            .min_by(|a, b| a.1.total_cmp(&b.1)) else {
+            index += 1;
+            continue;
+        };
+        if value - base < RISE {
+            index += 1;
+            continue;
+        }
+
+        // Follow it up until it stops climbing
+        let (mut peak, mut top) = (time, value);
+        while index + 1 < shallow.len() && shallow[index + 1].0 - peak <= SETTLE {
+            index += 1;
+            if shallow[index].1 > top {
+                (peak, top) = shallow[index];
+            }
+        }
+        let end = shallow[index].0;
+
+        // Water keeps moving down for a while after the top has settled
+        let before = stored_at(start).unwrap_or(0.0);
+        let after = stored.iter()
+            .filter(|(other, _)| start <= *other && *other <= end + 1.0)
+            .map(|(_, storage)| *storage)
+            .fold(before, f64::max);
+        let applied = (after - before).max(0.0);
+        // Rain overnight can land on the gauge's previous day
+        let rain = rain_between(rain, start - 1.0, end);
+        let kind = if rain >= MIN_RAIN && rain >= applied / 2.0 { EventKind::Rain } else { EventKind::Irrigation };
+
+        events.push(WettingEvent { start, peak, end, rise: top - base, applied, rain, kind });
+        index += 1;
+    }
+    events
+}
+
+// One line per event for the notes
+pub fn describe(event: &WettingEvent) -> String {
+    format!("{}: {} - shallow sensors up {:.1} points, profile gained {:.2} in, {:.2} in of rain.",
+        dates::label(event.start), event.kind.name(), event.rise, event.applied, event.rain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Three days of two hourly readings from July 1st, every sensor at 25% until the shallow
+    // ones climb 5 points over the morning of the 2nd
+    fn readings() -> Vec<(f64, Vec<Option<f64>>)> {
+        (0..36)
+            .map(|step| {
+                let time = 45474.0 + step as f64 / 12.0;
+                let shallow = 25.0 + 5.0 * ((step as f64 - 11.0).clamp(0.0, 3.0) / 3.0);
+                let readings = (0..aquaspy::DEPTHS.len()).map(|sensor| Some(if sensor < SHALLOW { shallow } else { 25.0 })).collect();
+                (time, readings)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dry_rise_is_irrigation() {
+        let events = detect(&readings(), &[(45474.0, 0.0), (45475.0, 0.0)]);
+        assert_eq!(events.len(), 1);
+        let event = events[0];
+        assert_eq!(event.kind, EventKind::Irrigation);
+        assert!(event.start < 45475.0 && 45475.0 <= event.peak && event.peak <= event.end);
+        assert!((event.rise - 5.0).abs() < 1e-9);
+        // 5 points on three 4 in layers
+        assert!((event.applied - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gauge_makes_it_rain() {
+        let events = detect(&readings(), &[(45475.0, 0.7)]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, EventKind::Rain);
+        assert_eq!(events[0].rain, 0.7);
+    }
+
+    #[test]
+    fn flat_readings_have_no_events() {
+        let flat: Vec<(f64, Vec<Option<f64>>)> = readings().into_iter().map(|(time, _)| (time, vec![Some(25.0); aquaspy::DEPTHS.len()])).collect();
+        assert!(detect(&flat, &[]).is_empty());
+        assert_eq!(storage(&flat[0].1), Some(25.0 / 100.0 * 4.0 * aquaspy::DEPTHS.len() as f64));
+        assert_eq!(storage(&[Some(25.0), None]), None);
+    }
+}
@@ -0,0 +1,132 @@
+// Growing degree days from daily max / min temperature (F)
+
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GddMethod {
+    SimpleAverage, // mean of max and min less the base, no cap
+    Corn,          // 86/50 method: max capped at the cap, both floored at the base
+    SingleSine,    // Baskerville & Emin sine curve with a horizontal cutoff at the cap
+}
+
+impl GddMethod {
+    pub const ALL: [GddMethod; 3] = [GddMethod::SimpleAverage, GddMethod::Corn, GddMethod::SingleSine];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GddMethod::SimpleAverage => "Simple average",
+            GddMethod::Corn => "86/50 corn",
+            GddMethod::SingleSine => "Single sine",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<GddMethod> {
+        GddMethod::ALL.into_iter().find(|method| method.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GddSettings {
+    pub method: GddMethod,
+    pub base: f64, // F
+    pub cap: f64,  // F
+}
+
+impl Default for GddSettings {
+    fn default() -> Self {
+        GddSettings { method: GddMethod::Corn, base: 50.0, cap: 86.0 }
+    }
+}
+
+impl GddSettings {
+    // Degree days for one day
+    pub fn daily(&self, max: f64, min: f64) -> f64 {
+        let (max, min) = (max.max(min), min.min(max));
+        match self.method {
+            GddMethod::SimpleAverage => ((max + min) / 2.0 - self.base).max(0.0),
+            GddMethod::Corn => {
+                let max = max.clamp(self.base, self.cap);
+                let min = min.clamp(self.base, self.cap);
+                (max + min) / 2.0 - self.base
+            },
+            GddMethod::SingleSine => single_sine(max, min, self.base, self.cap),
+        }
+    }
+
+    // Degree days summed from planting, one entry per day of temperatures
+    //
+    // The sheets start after planting, so `start` is whatever had built up before the first
+    // day of data (0 when planting is inside the data).
+    pub fn accumulate(&self, temperatures: &[(f64, f64, f64)], planting: f64, start: f64) -> Vec<(f64, f64)> {
+        let mut total = start;
+        temperatures.iter()
+            .filter(|(day, _, _)| *day >= planting)
+            .map(|&(day, max, min)| {
+                total += self.daily(max, min);
+                (day, total)
+            })
+            .collect()
+    }
+}
+
+// Area under a sine through the day's max and min, between the base and the cap
+fn single_sine(max: f64, min: f64, base: f64, cap: f64) -> f64 {
+    if max <= base {
+        return 0.0;
+    }
+    if min >= cap {
+        return cap - base;
+    }
+    let mean = (max + min) / 2.0;
+    let amplitude = (max - min) / 2.0;
+    if amplitude == 0.0 {
+        return (mean - base).clamp(0.0, cap - base);
+    }
+    // Where the curve crosses the base and the cap (-pi/2 / pi/2 when it never does)
+    let low = ((base - mean) / amplitude).clamp(-1.0, 1.0).asin();
+    let high = ((cap - mean) / amplitude).clamp(-1.0, 1.0).asin();
+    ((mean - base) * (high - low) + amplitude * (low.cos() - high.cos()) + (cap - base) * (PI / 2.0 - high)) / PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn method(method: GddMethod) -> GddSettings {
+        GddSettings { method, ..GddSettings::default() }
+    }
+
+    #[test]
+    fn corn_caps_at_86_and_floors_at_50() {
+        let corn = method(GddMethod::Corn);
+        assert_eq!(corn.daily(86.0, 50.0), 18.0);
+        assert_eq!(corn.daily(95.0, 45.0), 18.0);
+        assert_eq!(corn.daily(50.0, 40.0), 0.0);
+        // Only the simple average lets the heat through
+        assert_eq!(method(GddMethod::SimpleAverage).daily(95.0, 45.0), 20.0);
+        assert_eq!(method(GddMethod::SimpleAverage).daily(55.0, 35.0), 0.0);
+    }
+
+    #[test]
+    fn single_sine_between_base_and_cap() {
+        let sine = method(GddMethod::SingleSine);
+        assert_eq!(sine.daily(50.0, 30.0), 0.0);
+        assert_eq!(sine.daily(100.0, 90.0), 36.0);
+        // Never touching either line is the plain average
+        assert!((sine.daily(80.0, 60.0) - 20.0).abs() < 1e-9);
+        // A hot afternoon gets cut off at the cap
+        let hot = sine.daily(100.0, 70.0);
+        assert!(hot > 0.0 && hot < 35.0);
+        // A cold night still counts its warm hours, without raising the night to the base
+        let cold = sine.daily(70.0, 40.0);
+        assert!(method(GddMethod::SimpleAverage).daily(70.0, 40.0) < cold && cold < method(GddMethod::Corn).daily(70.0, 40.0));
+    }
+
+    #[test]
+    fn accumulate_from_planting_and_carried_over() {
+        let corn = GddSettings::default();
+        let temperatures = [(1.0, 80.0, 60.0), (2.0, 80.0, 60.0), (3.0, 80.0, 60.0)];
+        assert_eq!(corn.accumulate(&temperatures, 2.0, 0.0), vec![(2.0, 20.0), (3.0, 40.0)]);
+        assert_eq!(corn.accumulate(&temperatures, 0.0, 100.0).last(), Some(&(3.0, 160.0)));
+    }
+}
@@ -0,0 +1,102 @@
+// Historical weekly rainfall from summarized_pr.xlsx
+//
+// One row per year and week, same week names as simulated_df.xlsx, rows aren't in any order.
+// Totals in the file are mm, we keep inches.
+
+use calamine::{Data, Range};
+
+use crate::data;
+use crate::rainfall;
+
+pub const FILE: &str = "summarized_pr.xlsx";
+pub const SHEET: &str = "Sheet 1";
+
+// Rain that fell in one week of one past year
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeekTotal {
+    pub year: i64,
+    pub date_range: String,
+    pub total: f64,
+}
+
+pub fn totals(range: &Range<Data>) -> Vec<WeekTotal> {
+    range.rows()
+        .skip(1)
+        .filter_map(|row| {
+            let year = row.first().and_then(data::cell_f64)?;
+            let Some(Data::String(date_range)) = row.get(1) else { return None };
+            let total = row.get(2).and_then(data::cell_f64)?;
+            Some(WeekTotal { year: year as i64, date_range: date_range.clone(), total: total / 25.4 })
+        })
+        .collect()
+}
+
+// Week names in season order (placed in `year` to sort them)
+pub fn week_names(totals: &[WeekTotal], year: i64) -> Vec<String> {
+    let mut names: Vec<(f64, String)> = Vec::new();
+    for total in totals {
+        if names.iter().any(|(_, name)| *name == total.date_range) {
+            continue;
+        }
+        if let Some((start, _)) = rainfall::parse_week(&total.date_range, year) {
+            names.push((start, total.date_range.clone()));
+        }
+    }
+    names.sort_by(|a, b| a.0.total_cmp(&b.0));
+    names.into_iter().map(|(_, name)| name).collect()
+}
+
+// Every year in the file, oldest first
+pub fn years(totals: &[WeekTotal]) -> Vec<i64> {
+    let mut years: Vec<i64> = totals.iter().map(|total| total.year).collect();
+    years.sort();
+    years.dedup();
+    years
+}
+
+pub fn total(totals: &[WeekTotal], year: i64, date_range: &str) -> Option<f64> {
+    totals.iter().find(|total| total.year == year && total.date_range == date_range).map(|total| total.total)
+}
+
+// How many past years were drier and how many there are for a week
+pub fn rank(totals: &[WeekTotal], date_range: &str, observed: f64) -> (usize, usize) {
+    let past: Vec<f64> = totals.iter().filter(|total| total.date_range == date_range).map(|total| total.total).collect();
+    (past.iter().filter(|total| **total < observed).count(), past.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A sheet like summarized_pr.xlsx, totals in mm and the rows out of order
+    fn sheet() -> Range<Data> {
+        let rows = [(2021, "July 29-Aug 4", 25.4), (2020, "July 22-28", 0.0), (2021, "July 22-28", 50.8), (2020, "July 29-Aug 4", 12.7), (2019, "July 22-28", 25.4)];
+        let mut range = Range::new((0, 0), (rows.len() as u32, 2));
+        range.set_value((0, 0), Data::String("Year".to_string()));
+        for (index, (year, date_range, total)) in rows.iter().enumerate() {
+            let row = index as u32 + 1;
+            range.set_value((row, 0), Data::Float(*year as f64));
+            range.set_value((row, 1), Data::String(date_range.to_string()));
+            range.set_value((row, 2), Data::Float(*total));
+        }
+        range
+    }
+
+    #[test]
+    fn totals_in_inches_by_year_and_week() {
+        let totals = totals(&sheet());
+        assert_eq!(totals.len(), 5);
+        assert_eq!(years(&totals), vec![2019, 2020, 2021]);
+        assert_eq!(week_names(&totals, 2024), vec!["July 22-28", "July 29-Aug 4"]);
+        assert_eq!(total(&totals, 2021, "July 22-28"), Some(2.0));
+        assert_eq!(total(&totals, 2019, "July 29-Aug 4"), None);
+    }
+
+    #[test]
+    fn rank_against_past_years() {
+        let totals = totals(&sheet());
+        assert_eq!(rank(&totals, "July 22-28", 1.5), (2, 3));
+        assert_eq!(rank(&totals, "July 22-28", 0.0), (0, 3));
+        assert_eq!(rank(&totals, "Aug 5-11", 1.0), (0, 0));
+    }
+}
@@ -0,0 +1,210 @@
+// Irrigation decisions teams have logged, kept in the browser's localStorage
+//
+// Moves in and out as JSON (everything as it's stored) or CSV (for spreadsheets). Dates are
+// "YYYY-MM-DD" so both files read well by hand.
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::dates;
+
+// localStorage key the log lives under
+const KEY: &str = "irrigation_log";
+const CSV_HEADER: &str = "team,date,amount,method,note";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Method {
+    Pivot,
+    Drip,
+    Furrow,
+    Other,
+}
+
+impl Method {
+    pub const ALL: [Method; 4] = [Method::Pivot, Method::Drip, Method::Furrow, Method::Other];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::Pivot => "Pivot",
+            Method::Drip => "Drip",
+            Method::Furrow => "Furrow",
+            Method::Other => "Other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Method> {
+        Method::ALL.into_iter().find(|method| method.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub team: u32,
+    pub date: String, // YYYY-MM-DD
+    pub amount: f64,  // inches
+    pub method: Method,
+    pub note: String,
+}
+
+impl Entry {
+    // Excel serial of the date, None if it isn't a real date
+    pub fn day(&self) -> Option<f64> {
+        dates::parse_iso(&self.date)
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} {:.2} in", self.method.name(), self.amount)
+    }
+}
+
+// Whatever is saved, nothing if the storage is empty or unreadable
+pub fn load() -> Vec<Entry> {
+    LocalStorage::get(KEY).unwrap_or_default()
+}
+
+pub fn save(log: &[Entry]) {
+    if let Err(e) = LocalStorage::set(KEY, log) {
+        gloo::console::error!(format!("Couldn't save the irrigation log: {e}"));
+    }
+}
+
+// One team's entries in date order
+pub fn for_team(log: &[Entry], team: u32) -> Vec<&Entry> {
+    let mut entries: Vec<&Entry> = log.iter().filter(|entry| entry.team == team).collect();
+    entries.sort_by(|a, b| a.date.cmp(&b.date));
+    entries
+}
+
+// Irrigation (day, inches) a team logged, entries with a bad date are left out
+pub fn applied(log: &[Entry], team: u32) -> Vec<(f64, f64)> {
+    for_team(log, team).iter().filter_map(|entry| Some((entry.day()?, entry.amount))).collect()
+}
+
+// Entries from an import added to the log, leaving out any it already has
+pub fn merge(log: &mut Vec<Entry>, imported: Vec<Entry>) {
+    for entry in imported {
+        if !log.contains(&entry) {
+            log.push(entry);
+        }
+    }
+}
+
+pub fn to_json(log: &[Entry]) -> String {
+    serde_json::to_string_pretty(log).unwrap_or_default()
+}
+
+pub fn from_json(text: &str) -> Result<Vec<Entry>, String> {
+    let log: Vec<Entry> = serde_json::from_str(text).map_err(|e| format!("Not an irrigation log: {e}"))?;
+    check(log)
+}
+
+pub fn to_csv(log: &[Entry]) -> String {
+    let mut csv = CSV_HEADER.to_string() + "\n";
+    for entry in log {
+        csv += &format!("{},{},{},{},{}\n", entry.team, entry.date, entry.amount, entry.method.name(), quote(&entry.note));
+    }
+    csv
+}
+
+pub fn from_csv(text: &str) -> Result<Vec<Entry>, String> {
+    let mut log = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || (index == 0 && line.trim().eq_ignore_ascii_case(CSV_HEADER)) {
+            continue;
+        }
+        let fields = split(line);
+        let [team, date, amount, method, note] = fields.as_slice() else {
+            return Err(format!("Line {}: expected {CSV_HEADER}", index + 1));
+        };
+        log.push(Entry {
+            team: team.trim().parse().map_err(|_| format!("Line {}: bad team \"{team}\"", index + 1))?,
+            date: date.trim().to_string(),
+            amount: amount.trim().parse().map_err(|_| format!("Line {}: bad amount \"{amount}\"", index + 1))?,
+            method: Method::from_name(method).ok_or(format!("Line {}: unknown method \"{method}\"", index + 1))?,
+            note: note.clone(),
+        });
+    }
+    check(log)
+}
+
+// Dates have to be real and amounts can't be negative
+fn check(log: Vec<Entry>) -> Result<Vec<Entry>, String> {
+    match log.iter().find(|entry| entry.day().is_none() || entry.amount < 0.0) {
+        Some(entry) => Err(format!("Bad entry for team #{} on \"{}\" ({} in)", entry.team, entry.date, entry.amount)),
+        None => Ok(log),
+    }
+}
+
+// Notes can have commas and quotes in them
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// One CSV line into its fields, quoted fields can hold commas and doubled quotes
+fn split(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(team: u32, date: &str, amount: f64, method: Method, note: &str) -> Entry {
+        Entry { team, date: date.to_string(), amount, method, note: note.to_string() }
+    }
+
+    fn log() -> Vec<Entry> {
+        vec![
+            entry(2, "2024-07-01", 1.0, Method::Pivot, ""),
+            entry(2, "2024-07-04", 0.5, Method::Pivot, "after the storm, half a pass"),
+            entry(16, "2024-07-08", 0.75, Method::Drip, "zone \"B\" only"),
+            entry(16, "2024-07-15", 1.25, Method::Other, "\"quoted\", with, commas"),
+        ]
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let csv = to_csv(&log());
+        assert!(csv.starts_with(CSV_HEADER));
+        assert_eq!(from_csv(&csv), Ok(log()));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        assert_eq!(from_json(&to_json(&log())), Ok(log()));
+    }
+
+    #[test]
+    fn bad_rows_are_refused() {
+        assert!(from_csv("2,2024-07-01,1,Pivot").is_err());
+        assert!(from_csv("2,2024-07-01,lots,Pivot,").is_err());
+        assert!(from_csv("2,2024-02-30x,1,Pivot,").is_err());
+        assert!(from_csv("2,2024-07-01,-1,Pivot,").is_err());
+    }
+
+    #[test]
+    fn merge_skips_what_is_already_logged() {
+        let mut log = log();
+        merge(&mut log, vec![entry(2, "2024-07-01", 1.0, Method::Pivot, ""), entry(4, "2024-07-01", 1.0, Method::Pivot, "")]);
+        assert_eq!(log.len(), 5);
+        assert_eq!(applied(&log, 4), vec![(dates::from_ymd(2024, 7, 1), 1.0)]);
+    }
+}
@@ -1,30 +1,177 @@
-use std::io::Cursor;
+mod allocation;
+mod assistant;
+mod budget;
+mod chart;
+mod crop;
+mod data;
+mod dates;
+mod drawdown;
+mod equipment;
+mod events;
+mod gdd;
+mod history;
+mod irrigation_log;
+mod ndvi;
+mod partition;
+mod percolation;
+mod projection;
+mod rain_generator;
+mod rainfall;
+mod recommend;
+mod reference_et;
+mod roots;
+mod salinity;
+mod scenario;
+mod scoring;
+mod soil;
+mod soil_temperature;
+mod stress;
+mod submission;
+mod vpd;
+mod water_balance;
+
+use std::collections::HashMap;
 
 // Import necessary libraries for plotting, web canvas, and Yew framework
+use calamine::{Data, Range};
 use plotters::prelude::*;
 use plotters_canvas::CanvasBackend;
-use reqwest;
-use web_sys::HtmlCanvasElement;
+use web_sys::{HtmlCanvasElement, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use gloo::console; // For logging messages to the browser console
-use calamine::{open_workbook_auto_from_rs, Reader}; // parse xlsx files
+
+use allocation::AllocationSettings;
+use assistant::Assistant;
+use chart::{Gap, Marker, Series};
+use crop::{Crop, StageSpan};
+use data::{aquaspy, arable};
+use gdd::{GddMethod, GddSettings};
+use irrigation_log::{Entry, Method};
+use rain_generator::SimulationSettings;
+use recommend::{Outlook, Pivot};
+use reference_et::Surface;
+use scenario::{RainSource, Scenario, Schedule, WhatIfSettings};
+use scoring::{Column, Economics};
+use water_balance::{BalanceSettings, DayBalance, DayInput, EtSource, KcSource};
 
 // Enum to define the different plot messages that can trigger a plot update
+#[derive(Clone)]
 pub enum PlotMessage {
     HelloWorld(String, String, usize, usize, String),
+    WaterBalance(u32), // Simulated vs observed root zone depletion for a team
+    Recommendation(u32), // Irrigation plan for the next week for a team
+    GrowingDegreeDays(u32), // Our GDD against Arable's for a team
+    ReferenceEt(u32), // ASCE reference ET against Arable's ET for a team
+    Vpd(u32), // Vapour pressure deficit and high demand days for a team
+    StressDays, // Heat, water and VPD stress days by crop stage for every team
+    Allocation, // Logged irrigation against the allocation and its pumping cost for every team
+    Scenarios(u32), // What-if irrigation schedules replayed through a team's season
+    CropCoefficient(u32), // NDVI, canopy cover and the crop coefficient from them for a team
+    EtPartition(u32, bool), // Soil evaporation and transpiration for a team (daily or cumulative)
+    WaterBudget(u32), // Cumulative rain and irrigation against ET from planting for a team
+    RainfallSimulation, // Monte Carlo weekly rainfall against the statistics it was fitted to
+    RainfallHistory, // Past years' weekly rainfall with this season's on top
+    WaterNeed(u32), // Spread of the irrigation still needed to the end of the season for a team
+    WettingEvents(u32), // Rain and irrigation found in a team's AquaSpy readings
+    DeepPercolation(u32), // How deep every event wetted and what went past the roots for a team
+    Drawdown(u32), // Crop water use from the probe drying out against Arable's ET for a team
+    RootDepth(u32), // Deepest depth the roots are pulling water from for a team
+    PoreEc(u32), // Pore-water EC at every depth over the season for a team
+    SoilTemperature(u32), // Soil temperature profile, heat flow and alerts for a team
     None,
 }
 
+impl PlotMessage {
+    // Team the plot is for, if it's for one
+    fn team(&self) -> Option<u32> {
+        match self {
+            PlotMessage::HelloWorld(_, sheet, ..) => data::sheet_team(sheet),
+            PlotMessage::WaterBalance(team)
+            | PlotMessage::Recommendation(team)
+            | PlotMessage::GrowingDegreeDays(team)
+            | PlotMessage::ReferenceEt(team)
+            | PlotMessage::Vpd(team)
+            | PlotMessage::CropCoefficient(team)
+            | PlotMessage::EtPartition(team, _)
+            | PlotMessage::WaterBudget(team)
+            | PlotMessage::WaterNeed(team)
+            | PlotMessage::WettingEvents(team)
+            | PlotMessage::DeepPercolation(team)
+            | PlotMessage::Drawdown(team)
+            | PlotMessage::RootDepth(team)
+            | PlotMessage::PoreEc(team)
+            | PlotMessage::SoilTemperature(team)
+            | PlotMessage::Scenarios(team) => Some(*team),
+            PlotMessage::StressDays | PlotMessage::Allocation | PlotMessage::RainfallSimulation | PlotMessage::RainfallHistory | PlotMessage::None => None,
+        }
+    }
+}
+
 // Enum to handle messages in the App component
 pub enum Message {
     UpdatePlot(PlotMessage), // Trigger an update to the plot based on the selected message
     MakePlot(String, Vec<(f32, f32)>),
+    MakeChart(String, Vec<Series>, Vec<Marker>), // Draw several series on one chart, with markers for events
+    MakeBars(String, Vec<String>, Vec<Series>, Vec<Series>), // Draw grouped bars (caption, group names, bars, markers)
+    MakeStacked(String, Vec<Series>, Vec<Marker>), // Draw series stacked as areas, with markers for events
+    MakeGapChart(String, Vec<Series>, Gap, Vec<Marker>), // Draw lines with the gap between two of them shaded
+    MakeProfile(String, String, Vec<Series>), // Draw values against depth (caption, x label, series)
+    UpdateBalance(BalanceSettings), // Soil plot / crop / planting date picked in the sidebar
+    UpdatePivot(Pivot), // Pivot capacity / efficiency typed in the sidebar
+    UpdateGdd(GddSettings), // GDD method / base / cap picked in the sidebar
+    UpdateRain(SimulationSettings), // Seed / number of seasons for the rainfall simulator
+    UpdateAsOf(Option<f64>), // Day the water need projection starts from (None for the latest data)
+    ShowNotes(String, Vec<String>), // Text shown next to the chart (title, lines)
+    ShowTable(String, Vec<String>, Vec<Vec<String>>), // Table shown next to the chart (title, header, rows)
+    UpdateStages(Vec<StageSpan>), // Crop stages worked out for the strip under the charts
+    ToggleAssistant, // Show / hide the equipment assistant
+    ToggleLog, // Show / hide the irrigation log
+    UpdateDraft(Entry), // Irrigation log entry being typed in
+    LogIrrigation, // Add the draft to the irrigation log
+    RemoveIrrigation(usize), // Take an entry out of the irrigation log (index into the log)
+    ImportLog(web_sys::File), // JSON / CSV file picked to import into the log
+    LogImported(String, Result<String, String>), // File name and what was read from it
+    UpdateAllocation(AllocationSettings), // Allocation, field and pumping plant typed in the sidebar
+    BuildSubmission(u32), // Make a team's decision workbook
+    SubmissionReady(u32, Vec<u8>), // Team and the .xlsx bytes to offer for download
+    ToggleLeaderboard, // Show / hide the profit and efficiency leaderboard
+    UpdateYield(u32, Option<f64>), // Team's harvested yield (bu/acre), None to clear it
+    UpdateEconomics(Economics), // Grain price, other costs and dryland yield for the leaderboard
+    SortLeaderboard(Column), // Column clicked, clicking the same one again flips the order
+    UpdateWhatIf(WhatIfSettings), // Day the scenarios take over and where their rain comes from
+    UpdateScenario(usize, Scenario), // A scenario edited (index into the list)
+    AddScenario, // Another scenario to compare
+    RemoveScenario(usize), // Take a scenario out of the comparison
     None
 }
 
 // Main application structure containing a reference to the canvas
 pub struct App {
     plot: NodeRef, // NodeRef for accessing the canvas element
+    balance: BalanceSettings, // Settings the water balance model runs with
+    pivot: Pivot, // Pivot the irrigation plans are made for
+    gdd: GddSettings, // How growing degree days are worked out
+    rain: SimulationSettings, // How the rainfall simulator draws seasons
+    as_of: Option<f64>, // Projections start from this day instead of the latest data
+    notes: Option<(String, Vec<String>)>, // Explanation of the current chart, if it has one
+    table: Option<(String, Vec<String>, Vec<Vec<String>>)>, // Numbers behind the current chart, if it has a table
+    show_assistant: bool, // Equipment assistant panel open
+    team: Option<u32>, // Team the current chart is for, its logged irrigation gets marked
+    shown: Option<PlotMessage>, // What the current chart was drawn from, redrawn when the log changes
+    log: Vec<Entry>, // Irrigation decisions, saved to localStorage on every change
+    draft: Entry, // Log entry being typed in
+    show_log: bool, // Irrigation log panel open
+    import: Option<String>, // How the last import went
+    reader: Option<gloo::file::callbacks::FileReader>, // Import being read, dropping it cancels the read
+    allocation: AllocationSettings, // Season allocation and what pumping costs
+    submission: Option<(u32, gloo::file::ObjectUrl)>, // Last workbook made, the URL goes away when it's replaced
+    yields: std::collections::BTreeMap<u32, f64>, // Harvested yields by team, saved to localStorage
+    economics: Economics, // Prices and costs profit is worked out with
+    show_leaderboard: bool, // Leaderboard panel open
+    sort: (Column, bool), // Leaderboard column and whether it's biggest first
+    what_if: WhatIfSettings, // How the scenarios are replayed
+    scenarios: Vec<Scenario>, // Irrigation schedules to compare
+    stages: Vec<StageSpan>, // Crop stages drawn under every time series chart
 }
 
 // Implement the Component trait for the App struct
@@ -33,9 +180,45 @@ impl Component for App {
     type Properties = (); // No properties are needed for this component
 
     // Function to create the App component instance
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let balance = BalanceSettings::default();
+        let gdd = GddSettings::default();
+        ctx.link().send_future(App::fetch_stages(balance.crop, balance.planting, gdd));
+        let what_if = WhatIfSettings { from: balance.planting, rain: RainSource::Recorded };
+        let draft = Entry {
+            team: data::ARABLE_TEAMS[0],
+            date: dates::iso(balance.planting),
+            amount: 1.0,
+            method: Method::Pivot,
+            note: String::new(),
+        };
+
         App {
             plot: NodeRef::default(), // Initialize NodeRef for the canvas
+            balance,
+            pivot: Pivot::default(),
+            gdd,
+            rain: SimulationSettings::default(),
+            as_of: None,
+            notes: None,
+            table: None,
+            show_assistant: false,
+            team: None,
+            shown: None,
+            log: irrigation_log::load(),
+            draft,
+            show_log: false,
+            import: None,
+            reader: None,
+            allocation: AllocationSettings::default(),
+            submission: None,
+            yields: scoring::load_yields(),
+            economics: Economics::default(),
+            show_leaderboard: false,
+            sort: (Column::Profit, true),
+            what_if,
+            scenarios: Scenario::defaults(),
+            stages: Vec::new(),
         }
     }
 
@@ -43,35 +226,89 @@ impl Component for App {
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Message::UpdatePlot(plot_message) => {
+                self.notes = None; // Whatever was explained belonged to the old plot
+                self.table = None;
+                self.team = plot_message.team();
+                self.shown = Some(plot_message.clone());
                 // Handle the specific plot messages to draw the graph
                 match plot_message {
                     PlotMessage::HelloWorld(x, y, z, a, b) => {
-                        // Log message to console for debugging
-                        console::log!("Hello!");
-
                         ctx.link().send_future(App::fetch_data(x, y, z, a, b));
                     },
+                    PlotMessage::WaterBalance(team) => {
+                        ctx.link().send_future(App::fetch_water_balance(team, self.balance.clone(), self.pivot, irrigation_log::applied(&self.log, team)));
+                    },
+                    PlotMessage::Recommendation(team) => {
+                        ctx.link().send_future_batch(App::fetch_recommendation(team, self.balance.clone(), self.pivot, irrigation_log::applied(&self.log, team)));
+                    },
+                    PlotMessage::GrowingDegreeDays(team) => {
+                        ctx.link().send_future_batch(App::fetch_gdd(team, self.gdd, self.balance.planting));
+                    },
+                    PlotMessage::ReferenceEt(team) => {
+                        ctx.link().send_future_batch(App::fetch_reference_et(team));
+                    },
+                    PlotMessage::CropCoefficient(team) => {
+                        ctx.link().send_future_batch(App::fetch_crop_coefficient(team, self.balance.clone()));
+                    },
+                    PlotMessage::RainfallSimulation => {
+                        ctx.link().send_future_batch(App::fetch_rainfall_simulation(self.rain, dates::to_ymd(self.balance.planting).0));
+                    },
+                    PlotMessage::RainfallHistory => {
+                        ctx.link().send_future_batch(App::fetch_rainfall_history());
+                    },
+                    PlotMessage::WaterNeed(team) => {
+                        ctx.link().send_future_batch(App::fetch_water_need(team, self.balance.clone(), self.pivot, self.rain, self.as_of, irrigation_log::applied(&self.log, team)));
+                    },
+                    PlotMessage::WettingEvents(team) => {
+                        ctx.link().send_future_batch(App::fetch_wetting_events(team));
+                    },
+                    PlotMessage::DeepPercolation(team) => {
+                        ctx.link().send_future_batch(App::fetch_deep_percolation(team));
+                    },
+                    PlotMessage::Drawdown(team) => {
+                        ctx.link().send_future_batch(App::fetch_drawdown(team));
+                    },
+                    PlotMessage::RootDepth(team) => {
+                        ctx.link().send_future_batch(App::fetch_root_depth(team, self.balance.clone()));
+                    },
+                    PlotMessage::PoreEc(team) => {
+                        ctx.link().send_future_batch(App::fetch_pore_ec(team));
+                    },
+                    PlotMessage::SoilTemperature(team) => {
+                        ctx.link().send_future_batch(App::fetch_soil_temperature(team));
+                    },
+                    PlotMessage::Vpd(team) => {
+                        ctx.link().send_future_batch(App::fetch_vpd(team));
+                    },
+                    PlotMessage::StressDays => {
+                        ctx.link().send_future_batch(App::fetch_stress_days(self.balance.clone(), self.pivot, self.stages.clone(), self.log.clone()));
+                    },
+                    PlotMessage::EtPartition(team, cumulative) => {
+                        ctx.link().send_future_batch(App::fetch_et_partition(team, cumulative));
+                    },
+                    PlotMessage::WaterBudget(team) => {
+                        ctx.link().send_future_batch(App::fetch_water_budget(team, self.balance.planting, self.pivot, self.stages.clone(), irrigation_log::applied(&self.log, team)));
+                    },
+                    PlotMessage::Allocation => {
+                        ctx.link().send_message_batch(App::allocation_tracker(&self.log, &self.allocation));
+                    },
+                    PlotMessage::Scenarios(team) => {
+                        let logged = irrigation_log::applied(&self.log, team);
+                        ctx.link().send_future_batch(App::fetch_scenarios(team, self.balance.clone(), self.pivot, self.rain, self.allocation, self.what_if, self.scenarios.clone(), logged));
+                    },
                     PlotMessage::None => {}, // No action for None message
                 }
                 true // Indicate that the state has changed
             },
             Message::None => false,
             Message::MakePlot(caption, vec) => {
-                // Get the canvas element from the NodeRef
-                        let element: HtmlCanvasElement = self.plot.cast().unwrap();
-                        let parent = element.parent_element().unwrap();
-                        
-                        // Set canvas dimensions based on the parent element's size
-                        let rect = parent.get_bounding_client_rect();
-                        element.set_height(rect.height() as u32);
-                        element.set_width(rect.width() as u32);
-
                         // Initialize the backend for plotting using the canvas element
-                        let backend = CanvasBackend::with_canvas_object(element).unwrap();
+                        let backend = CanvasBackend::with_canvas_object(self.canvas()).unwrap();
                         
-                        // Create a drawing area for the plot
+                        // Create a drawing area for the plot, with the crop stages along the bottom
                         let drawing_area = backend.into_drawing_area();
                         drawing_area.fill(&RGBColor(200, 200, 200)).unwrap(); // Fill background with light gray
+                        let (drawing_area, strip_area) = chart::split_for_stages(drawing_area, &self.stages);
                         
                         let min_x = vec.clone().into_iter().min_by_key(|x| x.0 as u64).unwrap_or((f32::MAX, f32::MAX)).0;
                         let max_x = vec.clone().into_iter().max_by_key(|x| x.0 as u64).unwrap_or((f32::MIN, f32::MIN)).0;
@@ -79,7 +316,9 @@ impl Component for App {
                         let min_y = vec.clone().into_iter().min_by_key(|x| x.1 as u64).unwrap_or((f32::MAX, f32::MAX)).1;
                         let max_y = vec.clone().into_iter().max_by_key(|x| x.1 as u64).unwrap_or((f32::MIN, f32::MIN)).1;
 
-                        console::log!(format!("Max x, y = ({max_x}, {max_y})\nMim x, y = ({min_x}, {min_y})"));
+                        if let Some(strip_area) = &strip_area {
+                            chart::draw_stage_strip(strip_area, min_x as f64..max_x as f64, 30, &self.stages);
+                        }
 
                         // Build the chart with specific configurations
                         let mut chart = ChartBuilder::on(&drawing_area)
@@ -103,12 +342,184 @@ impl Component for App {
                             }
                         )).unwrap()
                         //.label("y = x^2") // Label for the legend
-                        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &RED)); // Legend line element
+                        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED)); // Legend line element
+
+                        // Logged irrigation for the team
+                        for marker in self.log_markers().iter().filter(|marker| min_x as f64 <= marker.day && marker.day <= max_x as f64) {
+                            let day = marker.day as f32;
+                            chart.draw_series(std::iter::once(PathElement::new(vec![(day, min_y - 1.0), (day, max_y + 1.0)], marker.color.stroke_width(1)))).unwrap();
+                            chart.draw_series(std::iter::once(Text::new(marker.label.clone(), (day, max_y + 1.0), ("sans-serif", 10).into_font().color(&marker.color)))).unwrap();
+                        }
 
                         drawing_area.present().unwrap();
 
                         true
             }, // No action needed
+            Message::MakeChart(caption, series, mut markers) => {
+                markers.extend(self.log_markers());
+                chart::draw_lines(self.canvas(), &caption, &series, &markers, &self.stages);
+                true
+            },
+            Message::MakeGapChart(caption, series, gap, mut markers) => {
+                markers.extend(self.log_markers());
+                chart::draw_lines_with_gap(self.canvas(), &caption, &series, Some(&gap), &markers, &self.stages);
+                true
+            },
+            Message::MakeStacked(caption, layers, mut markers) => {
+                markers.extend(self.log_markers());
+                chart::draw_stacked(self.canvas(), &caption, &layers, &markers, &self.stages);
+                true
+            },
+            Message::MakeBars(caption, groups, bars, markers) => {
+                chart::draw_grouped_bars(self.canvas(), &caption, &groups, &bars, &markers);
+                true
+            },
+            Message::MakeProfile(caption, x_label, series) => {
+                chart::draw_profile(self.canvas(), &caption, &x_label, &series);
+                true
+            },
+            Message::UpdateBalance(settings) => {
+                if settings.crop != self.balance.crop || settings.planting != self.balance.planting {
+                    ctx.link().send_future(App::fetch_stages(settings.crop, settings.planting, self.gdd));
+                }
+                self.balance = settings;
+                true
+            },
+            Message::UpdatePivot(pivot) => {
+                self.pivot = pivot;
+                true
+            },
+            Message::UpdateGdd(gdd) => {
+                ctx.link().send_future(App::fetch_stages(self.balance.crop, self.balance.planting, gdd));
+                self.gdd = gdd;
+                true
+            },
+            Message::UpdateRain(rain) => {
+                self.rain = rain;
+                true
+            },
+            Message::UpdateAsOf(as_of) => {
+                self.as_of = as_of;
+                true
+            },
+            Message::UpdateStages(stages) => {
+                self.stages = stages;
+                false // Shows up with the next chart
+            },
+            Message::ShowTable(title, header, rows) => {
+                self.table = Some((title, header, rows));
+                true
+            },
+            Message::ShowNotes(title, lines) => {
+                self.notes = Some((title, lines));
+                true
+            },
+            Message::ToggleAssistant => {
+                self.show_assistant = !self.show_assistant;
+                true
+            },
+            Message::UpdateWhatIf(what_if) => {
+                self.what_if = what_if;
+                true
+            },
+            Message::UpdateScenario(index, scenario) => {
+                if let Some(old) = self.scenarios.get_mut(index) {
+                    *old = scenario;
+                }
+                true
+            },
+            Message::AddScenario => {
+                self.scenarios.push(Scenario::weekly(&format!("Scenario {}", self.scenarios.len() + 1), 1.0, &[0]));
+                true
+            },
+            Message::RemoveScenario(index) => {
+                if index < self.scenarios.len() {
+                    self.scenarios.remove(index);
+                }
+                true
+            },
+            Message::ToggleLeaderboard => {
+                self.show_leaderboard = !self.show_leaderboard;
+                true
+            },
+            Message::UpdateYield(team, grain) => {
+                match grain {
+                    Some(grain) => self.yields.insert(team, grain),
+                    None => self.yields.remove(&team),
+                };
+                scoring::save_yields(&self.yields);
+                true
+            },
+            Message::UpdateEconomics(economics) => {
+                self.economics = economics;
+                true
+            },
+            Message::SortLeaderboard(column) => {
+                // Biggest first is what you want for everything but the team number
+                self.sort = if self.sort.0 == column { (column, !self.sort.1) } else { (column, column != Column::Team) };
+                true
+            },
+            Message::ToggleLog => {
+                self.show_log = !self.show_log;
+                true
+            },
+            Message::UpdateDraft(draft) => {
+                self.draft = draft;
+                false // The inputs already show it
+            },
+            Message::LogIrrigation => {
+                if self.draft.day().is_some() && self.draft.amount >= 0.0 {
+                    self.log.push(self.draft.clone());
+                    self.log_changed(ctx);
+                    self.draft.note.clear();
+                }
+                true
+            },
+            Message::RemoveIrrigation(index) => {
+                if index < self.log.len() {
+                    self.log.remove(index);
+                    self.log_changed(ctx);
+                }
+                true
+            },
+            Message::ImportLog(file) => {
+                let name = file.name();
+                let link = ctx.link().clone();
+                self.reader = Some(gloo::file::callbacks::read_as_text(&gloo::file::Blob::from(file), move |result| {
+                    link.send_message(Message::LogImported(name, result.map_err(|e| e.to_string())));
+                }));
+                false
+            },
+            Message::UpdateAllocation(allocation) => {
+                self.allocation = allocation;
+                false // The inputs already show it
+            },
+            Message::BuildSubmission(team) => {
+                let entries = irrigation_log::for_team(&self.log, team).into_iter().cloned().collect();
+                ctx.link().send_future_batch(App::build_submission(team, self.balance.clone(), self.pivot, entries, self.allocation.allocation));
+                false
+            },
+            Message::SubmissionReady(team, bytes) => {
+                let blob = gloo::file::Blob::new_with_options(bytes.as_slice(), Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"));
+                self.submission = Some((team, gloo::file::ObjectUrl::from(blob)));
+                true
+            },
+            Message::LogImported(name, result) => {
+                self.reader = None;
+                let parsed = result.and_then(|text| {
+                    if name.to_lowercase().ends_with(".csv") { irrigation_log::from_csv(&text) } else { irrigation_log::from_json(&text) }
+                });
+                self.import = Some(match parsed {
+                    Ok(imported) => {
+                        let before = self.log.len();
+                        irrigation_log::merge(&mut self.log, imported);
+                        self.log_changed(ctx);
+                        format!("{}: {} new entries.", name, self.log.len() - before)
+                    },
+                    Err(e) => format!("{name}: {e}"),
+                });
+                true
+            },
         }
     }
 
@@ -122,6 +533,16 @@ impl Component for App {
                 <main>
                     <div class="sidebar"> // Sidebar for buttons
                         <ul>
+                            <li>
+                                <button onclick={ctx.link().callback(|_| Message::ToggleAssistant)}>{ "Which equipment do I need?" }</button>
+                            </li>
+                            <li>
+                                <button onclick={ctx.link().callback(|_| Message::ToggleLog)}>{ "Irrigation log" }</button>
+                            </li>
+                            <li>
+                                <button onclick={ctx.link().callback(|_| Message::ToggleLeaderboard)}>{ "Profit and efficiency leaderboard" }</button>
+                            </li>
+                            <hr />
                             <li>
                                 <h2>{ "Team 12" }</h2>
                             </li>
@@ -387,11 +808,251 @@ impl Component for App {
                             <li>
                                 <button onclick={ctx.link().callback(|_| Message::UpdatePlot(PlotMessage::HelloWorld("data/24 KSU TAPS Arable.xlsx".to_string(), "Team #16 Data".to_string(), 0, 11, "Team #16 Max Temp / time".to_string())))}>{ "Team #16 Max Temp / time" }</button>
                             </li>
+
+                            <hr />
+                            <li>
+                                <h2>{ "Water balance" }</h2>
+                            </li>
+                            <hr />
+                            { self.balance_settings(ctx) }
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::WaterBalance(team)))}>{ format!("Team #{team} Root zone depletion / time") }</button>
+                                </li>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Irrigation plan" }</h2>
+                            </li>
+                            <hr />
+                            { self.pivot_settings(ctx) }
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::Recommendation(team)))}>{ format!("Team #{team} Irrigation plan / next week") }</button>
+                                </li>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Allocation and submission" }</h2>
+                            </li>
+                            <hr />
+                            { self.allocation_settings(ctx) }
+                            <li>
+                                <button onclick={ctx.link().callback(|_| Message::UpdatePlot(PlotMessage::Allocation))}>{ "Allocation and pumping cost, all teams" }</button>
+                            </li>
+                            { for data::teams().into_iter().map(|team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::BuildSubmission(team))}>{ format!("Team #{team} Make decision workbook") }</button>
+                                </li>
+                            }) }
+                            if let Some((team, url)) = &self.submission {
+                                <li>
+                                    <a href={url.to_string()} download={format!("TAPS team {team} decisions (unofficial).xlsx")}>{ format!("Download team #{team} workbook") }</a>
+                                </li>
+                                <li>{ format!("{}.", submission::NOT_OFFICIAL) }</li>
+                            }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Growing degree days" }</h2>
+                            </li>
+                            <hr />
+                            { self.gdd_settings(ctx) }
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::GrowingDegreeDays(team)))}>{ format!("Team #{team} Growing degree days / time") }</button>
+                                </li>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Reference ET" }</h2>
+                            </li>
+                            <hr />
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::ReferenceEt(team)))}>{ format!("Team #{team} Reference ET / time") }</button>
+                                </li>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Evaporation / transpiration" }</h2>
+                            </li>
+                            <hr />
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <>
+                                    <li>
+                                        <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::EtPartition(team, false)))}>{ format!("Team #{team} E / T daily") }</button>
+                                    </li>
+                                    <li>
+                                        <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::EtPartition(team, true)))}>{ format!("Team #{team} E / T cumulative") }</button>
+                                    </li>
+                                </>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Water budget" }</h2>
+                            </li>
+                            <hr />
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::WaterBudget(team)))}>{ format!("Team #{team} Water budget / time") }</button>
+                                </li>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Atmospheric demand" }</h2>
+                            </li>
+                            <hr />
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::Vpd(team)))}>{ format!("Team #{team} VPD / time") }</button>
+                                </li>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Stress days" }</h2>
+                            </li>
+                            <hr />
+                            <li>
+                                <button onclick={ctx.link().callback(|_| Message::UpdatePlot(PlotMessage::StressDays))}>{ "Stress days by stage, all teams" }</button>
+                            </li>
+
+                            <hr />
+                            <li>
+                                <h2>{ "Crop coefficient" }</h2>
+                            </li>
+                            <hr />
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::CropCoefficient(team)))}>{ format!("Team #{team} NDVI Kc / time") }</button>
+                                </li>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Rainfall simulator" }</h2>
+                            </li>
+                            <hr />
+                            { self.rain_settings(ctx) }
+                            <li>
+                                <button onclick={ctx.link().callback(|_| Message::UpdatePlot(PlotMessage::RainfallSimulation))}>{ "Simulated weekly rainfall / time" }</button>
+                            </li>
+
+                            <hr />
+                            <li>
+                                <h2>{ "Rainfall history" }</h2>
+                            </li>
+                            <hr />
+                            <li>
+                                <button onclick={ctx.link().callback(|_| Message::UpdatePlot(PlotMessage::RainfallHistory))}>{ "Weekly rainfall by year" }</button>
+                            </li>
+
+                            <hr />
+                            <li>
+                                <h2>{ "AquaSpy profile" }</h2>
+                            </li>
+                            <hr />
+                            { for data::AQUASPY_TEAMS.iter().map(|&team| html! {
+                                <>
+                                    <li>
+                                        <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::WettingEvents(team)))}>{ format!("Team #{team} Wetting events / time") }</button>
+                                    </li>
+                                    <li>
+                                        <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::DeepPercolation(team)))}>{ format!("Team #{team} Deep percolation / time") }</button>
+                                    </li>
+                                    <li>
+                                        <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::Drawdown(team)))}>{ format!("Team #{team} Drawdown water use / time") }</button>
+                                    </li>
+                                    <li>
+                                        <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::RootDepth(team)))}>{ format!("Team #{team} Rooting depth / time") }</button>
+                                    </li>
+                                    <li>
+                                        <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::PoreEc(team)))}>{ format!("Team #{team} Pore-water EC / time") }</button>
+                                    </li>
+                                    <li>
+                                        <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::SoilTemperature(team)))}>{ format!("Team #{team} Soil temperature / depth") }</button>
+                                    </li>
+                                </>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "Season water need" }</h2>
+                            </li>
+                            <hr />
+                            <li>
+                                <label>{ "As of " }
+                                    <input type="date" value={self.as_of.map(dates::iso).unwrap_or_default()}
+                                        onchange={ctx.link().callback(|e: Event| Message::UpdateAsOf(dates::parse_iso(&e.target_unchecked_into::<HtmlInputElement>().value())))} />
+                                </label>
+                            </li>
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::WaterNeed(team)))}>{ format!("Team #{team} Water still needed / time") }</button>
+                                </li>
+                            }) }
+
+                            <hr />
+                            <li>
+                                <h2>{ "What-if scenarios" }</h2>
+                            </li>
+                            <hr />
+                            { self.scenario_settings(ctx) }
+                            { for data::ARABLE_TEAMS.iter().map(|&team| html! {
+                                <li>
+                                    <button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::Scenarios(team)))}>{ format!("Team #{team} Compare scenarios / time") }</button>
+                                </li>
+                            }) }
                         </ul>                
                     </div>
                     <div class="information"> // Container for the canvas
                         <canvas ref={self.plot.clone()} /> // Canvas element for plotting
                     </div>
+                    if let Some((title, lines)) = &self.notes {
+                        <div class="notes"> // Reasoning behind the chart
+                            <h2>{ title }</h2>
+                            <ul>
+                                { for lines.iter().map(|line| html! { <li>{ line }</li> }) }
+                            </ul>
+                        </div>
+                    }
+                    if let Some((title, header, rows)) = &self.table {
+                        <div class="notes"> // Numbers behind the chart
+                            <h2>{ title }</h2>
+                            <table>
+                                <tr>
+                                    { for header.iter().map(|cell| html! { <th>{ cell }</th> }) }
+                                </tr>
+                                { for rows.iter().map(|row| html! {
+                                    <tr>
+                                        { for row.iter().map(|cell| html! { <td>{ cell }</td> }) }
+                                    </tr>
+                                }) }
+                            </table>
+                        </div>
+                    }
+                    if self.show_leaderboard {
+                        <div class="notes"> // Profit and water use efficiency by team
+                            { self.leaderboard(ctx) }
+                        </div>
+                    }
+                    if self.show_log {
+                        <div class="notes"> // Irrigation decisions
+                            { self.irrigation_log(ctx) }
+                        </div>
+                    }
+                    if self.show_assistant {
+                        <div class="notes"> // Equipment questionnaire and ranking
+                            <Assistant />
+                        </div>
+                    }
                 </main>
                 <footer>
                     <p>{ "Footer" }</p> // Placeholder for footer content
@@ -402,46 +1063,575 @@ impl Component for App {
 }
 
 impl App {
+    // Logged irrigation for the team the current chart is for
+    fn log_markers(&self) -> Vec<Marker> {
+        let Some(team) = self.team else {
+            return Vec::new();
+        };
+        irrigation_log::for_team(&self.log, team).iter()
+            .filter_map(|entry| Some(Marker::new(entry.day()?, format!("Logged {}", entry.label()), MAGENTA)))
+            .collect()
+    }
+
+    // Prices, every team's score with its yield typed in the table, sorted on a clicked column
+    fn leaderboard(&self, ctx: &Context<Self>) -> Html {
+        let economics = self.economics;
+        let on_change = move |set: fn(&mut Economics, f64)| ctx.link().callback(move |e: Event| {
+            let mut economics = economics;
+            if let Ok(value) = e.target_unchecked_into::<HtmlInputElement>().value().parse() {
+                set(&mut economics, value);
+            }
+            Message::UpdateEconomics(economics)
+        });
+
+        let mut scores: Vec<scoring::Score> = data::teams().into_iter()
+            .map(|team| {
+                let irrigation = irrigation_log::applied(&self.log, team).iter().fold(0.0, |total, (_, amount)| total + amount);
+                scoring::score(team, self.yields.get(&team).copied(), irrigation, &self.economics, &self.allocation)
+            })
+            .collect();
+        scoring::sort(&mut scores, self.sort.0, self.sort.1);
+
+        html! {
+            <>
+                <h2>{ "Leaderboard" }</h2>
+                <ul>
+                    <li>
+                        <label>{ "Grain price ($/bu) " }
+                            <input type="number" min="0" step="0.05" value={self.economics.price.to_string()} onchange={on_change(|economics, value| economics.price = value)} />
+                        </label>
+                    </li>
+                    <li>
+                        <label>{ "Other costs ($/ac) " }
+                            <input type="number" min="0" step="10" value={self.economics.other_costs.to_string()} onchange={on_change(|economics, value| economics.other_costs = value)} />
+                        </label>
+                    </li>
+                    <li>
+                        <label>{ "Dryland yield (bu/ac) " }
+                            <input type="number" min="0" step="1" value={self.economics.dryland.to_string()} onchange={on_change(|economics, value| economics.dryland = value)} />
+                        </label>
+                    </li>
+                    <li>{ "Irrigation is the team's log, pumped at the allocation settings' cost. Click a team for its water budget." }</li>
+                </ul>
+                <table>
+                    <tr>
+                        { for Column::ALL.into_iter().map(|column| {
+                            let arrow = match self.sort {
+                                (sorted, true) if sorted == column => " v",
+                                (sorted, false) if sorted == column => " ^",
+                                _ => "",
+                            };
+                            html! {
+                                <th onclick={ctx.link().callback(move |_| Message::SortLeaderboard(column))}>{ format!("{}{arrow}", column.name()) }</th>
+                            }
+                        }) }
+                    </tr>
+                    { for scores.iter().map(|score| {
+                        let team = score.team;
+                        let on_yield = ctx.link().callback(move |e: Event| Message::UpdateYield(team, e.target_unchecked_into::<HtmlInputElement>().value().parse().ok()));
+                        html! {
+                            <tr>
+                                { for Column::ALL.into_iter().map(|column| match column {
+                                    // The water budget needs the team's Arable, AquaSpy only teams just get their number
+                                    Column::Team if data::ARABLE_TEAMS.contains(&team) => html! {
+                                        <td><button onclick={ctx.link().callback(move |_| Message::UpdatePlot(PlotMessage::WaterBudget(team)))}>{ column.text(score) }</button></td>
+                                    },
+                                    Column::Team => html! {
+                                        <td title={format!("No Arable data for team #{team}")}>{ column.text(score) }</td>
+                                    },
+                                    Column::Yield => html! {
+                                        <td><input type="number" min="0" step="1" size="5" value={score.grain.map(|grain| grain.to_string()).unwrap_or_default()} onchange={on_yield.clone()} /></td>
+                                    },
+                                    _ => html! { <td>{ column.text(score) }</td> },
+                                }) }
+                            </tr>
+                        }
+                    }) }
+                </table>
+            </>
+        }
+    }
+
+    // Form for new entries, the log as a table and the import / export links
+    fn irrigation_log(&self, ctx: &Context<Self>) -> Html {
+        let draft = self.draft.clone();
+        let on_team = ctx.link().callback(move |e: Event| {
+            let mut draft = draft.clone();
+            draft.team = e.target_unchecked_into::<HtmlSelectElement>().value().parse().unwrap_or(draft.team);
+            Message::UpdateDraft(draft)
+        });
+        let draft = self.draft.clone();
+        let on_date = ctx.link().callback(move |e: Event| {
+            let mut draft = draft.clone();
+            draft.date = e.target_unchecked_into::<HtmlInputElement>().value();
+            Message::UpdateDraft(draft)
+        });
+        let draft = self.draft.clone();
+        let on_amount = ctx.link().callback(move |e: Event| {
+            let mut draft = draft.clone();
+            draft.amount = e.target_unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(draft.amount);
+            Message::UpdateDraft(draft)
+        });
+        let draft = self.draft.clone();
+        let on_method = ctx.link().callback(move |e: Event| {
+            let mut draft = draft.clone();
+            draft.method = Method::from_name(&e.target_unchecked_into::<HtmlSelectElement>().value()).unwrap_or(draft.method);
+            Message::UpdateDraft(draft)
+        });
+        let draft = self.draft.clone();
+        let on_note = ctx.link().callback(move |e: Event| {
+            let mut draft = draft.clone();
+            draft.note = e.target_unchecked_into::<HtmlInputElement>().value();
+            Message::UpdateDraft(draft)
+        });
+        let on_import = ctx.link().batch_callback(|e: Event| {
+            let input = e.target_unchecked_into::<HtmlInputElement>();
+            input.files().and_then(|files| files.get(0)).map(Message::ImportLog)
+        });
+
+        // Exports are data: links so there's no object URL to clean up
+        let link = |mime: &str, text: String| format!("data:{mime};charset=utf-8,{}", String::from(js_sys::encode_uri_component(&text)));
+        let mut entries: Vec<(usize, &Entry)> = self.log.iter().enumerate().collect();
+        entries.sort_by(|a, b| (a.1.team, &a.1.date).cmp(&(b.1.team, &b.1.date)));
+
+        html! {
+            <>
+                <h2>{ "Irrigation log" }</h2>
+                <ul>
+                    <li>
+                        <label>{ "Team " }
+                            <select onchange={on_team}>
+                                { for data::teams().into_iter().map(|team| html! {
+                                    <option selected={team == self.draft.team} value={team.to_string()}>{ format!("#{team}") }</option>
+                                }) }
+                            </select>
+                        </label>
+                    </li>
+                    <li>
+                        <label>{ "Date " }
+                            <input type="date" value={self.draft.date.clone()} onchange={on_date} />
+                        </label>
+                    </li>
+                    <li>
+                        <label>{ "Amount (in) " }
+                            <input type="number" min="0" step="0.05" value={self.draft.amount.to_string()} onchange={on_amount} />
+                        </label>
+                    </li>
+                    <li>
+                        <label>{ "Method " }
+                            <select onchange={on_method}>
+                                { for Method::ALL.into_iter().map(|method| html! {
+                                    <option selected={method == self.draft.method} value={method.name()}>{ method.name() }</option>
+                                }) }
+                            </select>
+                        </label>
+                    </li>
+                    <li>
+                        <label>{ "Note " }
+                            <input type="text" value={self.draft.note.clone()} onchange={on_note} />
+                        </label>
+                    </li>
+                    <li>
+                        <button onclick={ctx.link().callback(|_| Message::LogIrrigation)}>{ "Log irrigation" }</button>
+                    </li>
+                </ul>
+                <table>
+                    <tr>
+                        <th>{ "Team" }</th>
+                        <th>{ "Date" }</th>
+                        <th>{ "In" }</th>
+                        <th>{ "Method" }</th>
+                        <th>{ "Note" }</th>
+                        <th></th>
+                    </tr>
+                    { for entries.into_iter().map(|(index, entry)| html! {
+                        <tr>
+                            <td>{ format!("#{}", entry.team) }</td>
+                            <td>{ entry.date.clone() }</td>
+                            <td>{ format!("{:.2}", entry.amount) }</td>
+                            <td>{ entry.method.name() }</td>
+                            <td>{ entry.note.clone() }</td>
+                            <td><button onclick={ctx.link().callback(move |_| Message::RemoveIrrigation(index))}>{ "x" }</button></td>
+                        </tr>
+                    }) }
+                </table>
+                <ul>
+                    <li>
+                        <a href={link("application/json", irrigation_log::to_json(&self.log))} download="irrigation_log.json">{ "Export JSON" }</a>
+                        { " " }
+                        <a href={link("text/csv", irrigation_log::to_csv(&self.log))} download="irrigation_log.csv">{ "Export CSV" }</a>
+                    </li>
+                    <li>
+                        <label>{ "Import (JSON / CSV) " }
+                            <input type="file" accept=".json,.csv" onchange={on_import} />
+                        </label>
+                    </li>
+                    if let Some(import) = &self.import {
+                        <li>{ import }</li>
+                    }
+                </ul>
+            </>
+        }
+    }
+
+    // Canvas sized to fill its container, ready to be drawn on
+    fn canvas(&self) -> HtmlCanvasElement {
+        // Get the canvas element from the NodeRef
+        let element: HtmlCanvasElement = self.plot.cast().unwrap();
+        let parent = element.parent_element().unwrap();
+
+        // Set canvas dimensions based on the parent element's size
+        let rect = parent.get_bounding_client_rect();
+        element.set_height(rect.height() as u32);
+        element.set_width(rect.width() as u32);
+        element
+    }
+
+    // Inputs for the water balance model
+    fn balance_settings(&self, ctx: &Context<Self>) -> Html {
+        let settings = self.balance.clone();
+        let on_plot = ctx.link().callback(move |e: Event| {
+            let mut settings = settings.clone();
+            settings.plot_id = e.target_unchecked_into::<HtmlSelectElement>().value();
+            Message::UpdateBalance(settings)
+        });
+        let settings = self.balance.clone();
+        let on_crop = ctx.link().callback(move |e: Event| {
+            let mut settings = settings.clone();
+            settings.crop = Crop::from_name(&e.target_unchecked_into::<HtmlSelectElement>().value()).unwrap_or(settings.crop);
+            Message::UpdateBalance(settings)
+        });
+        let settings = self.balance.clone();
+        let on_et = ctx.link().callback(move |e: Event| {
+            let mut settings = settings.clone();
+            settings.et_source = EtSource::from_name(&e.target_unchecked_into::<HtmlSelectElement>().value()).unwrap_or(settings.et_source);
+            Message::UpdateBalance(settings)
+        });
+        let settings = self.balance.clone();
+        let on_kc = ctx.link().callback(move |e: Event| {
+            let mut settings = settings.clone();
+            settings.kc_source = KcSource::from_name(&e.target_unchecked_into::<HtmlSelectElement>().value()).unwrap_or(settings.kc_source);
+            Message::UpdateBalance(settings)
+        });
+        let settings = self.balance.clone();
+        let on_planting = ctx.link().callback(move |e: Event| {
+            let mut settings = settings.clone();
+            settings.planting = dates::parse_iso(&e.target_unchecked_into::<HtmlInputElement>().value()).unwrap_or(settings.planting);
+            Message::UpdateBalance(settings)
+        });
+
+        html! {
+            <>
+                // Which team farms which sampled plot isn't in the data, so one profile does for all
+                <li title="The texture samples aren't matched to teams, this plot's profile is used for every team's water balance">
+                    <label>{ "Soil plot (assumed for every team) " }
+                        <select onchange={on_plot}>
+                            { for soil::plot_ids().into_iter().map(|id| html! {
+                                <option selected={id == self.balance.plot_id} value={id.clone()}>{ id }</option>
+                            }) }
+                        </select>
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Crop " }
+                        <select onchange={on_crop}>
+                            { for Crop::ALL.iter().map(|crop| html! {
+                                <option selected={*crop == self.balance.crop}>{ crop.name() }</option>
+                            }) }
+                        </select>
+                    </label>
+                </li>
+                <li>
+                    <label>{ "ET " }
+                        <select onchange={on_et}>
+                            { for EtSource::ALL.iter().map(|source| html! {
+                                <option selected={*source == self.balance.et_source}>{ source.name() }</option>
+                            }) }
+                        </select>
+                    </label>
+                </li>
+                <li>
+                    // Only reference ET needs a crop coefficient
+                    <label>{ "Kc " }
+                        <select onchange={on_kc} disabled={self.balance.et_source.includes_crop()}>
+                            { for KcSource::ALL.iter().map(|source| html! {
+                                <option selected={*source == self.balance.kc_source}>{ source.name() }</option>
+                            }) }
+                        </select>
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Planted " }
+                        <input type="date" value={dates::iso(self.balance.planting)} onchange={on_planting} />
+                    </label>
+                </li>
+            </>
+        }
+    }
+
+    // Inputs for the irrigation plan
+    fn pivot_settings(&self, ctx: &Context<Self>) -> Html {
+        let pivot = self.pivot;
+        let on_capacity = ctx.link().callback(move |e: Event| {
+            let mut pivot = pivot;
+            pivot.capacity = e.target_unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(pivot.capacity);
+            Message::UpdatePivot(pivot)
+        });
+        let on_efficiency = ctx.link().callback(move |e: Event| {
+            let mut pivot = pivot;
+            pivot.efficiency = e.target_unchecked_into::<HtmlInputElement>().value().parse::<f64>().map(|percent| percent / 100.0).unwrap_or(pivot.efficiency);
+            Message::UpdatePivot(pivot)
+        });
+
+        html! {
+            <>
+                <li>
+                    <label>{ "Pivot capacity (in/day) " }
+                        <input type="number" min="0" step="0.01" value={self.pivot.capacity.to_string()} onchange={on_capacity} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Efficiency (%) " }
+                        <input type="number" min="1" max="100" step="1" value={(self.pivot.efficiency * 100.0).round().to_string()} onchange={on_efficiency} />
+                    </label>
+                </li>
+            </>
+        }
+    }
+
+    // Inputs for the allocation tracker and submissions
+    fn allocation_settings(&self, ctx: &Context<Self>) -> Html {
+        let allocation = self.allocation;
+        let on_change = move |set: fn(&mut AllocationSettings, f64)| ctx.link().callback(move |e: Event| {
+            let mut allocation = allocation;
+            if let Ok(value) = e.target_unchecked_into::<HtmlInputElement>().value().parse() {
+                set(&mut allocation, value);
+            }
+            Message::UpdateAllocation(allocation)
+        });
+
+        html! {
+            <>
+                <li>
+                    <label>{ "Allocation (in) " }
+                        <input type="number" min="0" step="0.5" value={self.allocation.allocation.to_string()} onchange={on_change(|allocation, value| allocation.allocation = value)} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Field (acres) " }
+                        <input type="number" min="0" step="1" value={self.allocation.area.to_string()} onchange={on_change(|allocation, value| allocation.area = value)} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Pumping lift (ft) " }
+                        <input type="number" min="0" step="10" value={self.allocation.lift.to_string()} onchange={on_change(|allocation, value| allocation.lift = value)} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Pressure (psi) " }
+                        <input type="number" min="0" step="1" value={self.allocation.pressure.to_string()} onchange={on_change(|allocation, value| allocation.pressure = value)} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Pumping efficiency (%) " }
+                        <input type="number" min="1" max="100" step="1" value={(self.allocation.efficiency * 100.0).round().to_string()} onchange={on_change(|allocation, value| allocation.efficiency = value / 100.0)} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Energy price ($/kWh) " }
+                        <input type="number" min="0" step="0.01" value={self.allocation.price.to_string()} onchange={on_change(|allocation, value| allocation.price = value)} />
+                    </label>
+                </li>
+            </>
+        }
+    }
+
+    // Save the log and redraw the chart, the water balance and everything built on it use the
+    // logged irrigation
+    fn log_changed(&self, ctx: &Context<Self>) {
+        irrigation_log::save(&self.log);
+        if let Some(plot) = self.shown.clone() {
+            ctx.link().send_message(Message::UpdatePlot(plot));
+        }
+    }
+
+    // Scenario editor: when they start, where the rain comes from and every schedule
+    fn scenario_settings(&self, ctx: &Context<Self>) -> Html {
+        let what_if = self.what_if;
+        let on_from = ctx.link().callback(move |e: Event| {
+            let mut what_if = what_if;
+            what_if.from = dates::parse_iso(&e.target_unchecked_into::<HtmlInputElement>().value()).unwrap_or(what_if.from);
+            Message::UpdateWhatIf(what_if)
+        });
+        let on_rain = ctx.link().callback(move |e: Event| {
+            let mut what_if = what_if;
+            what_if.rain = RainSource::from_name(&e.target_unchecked_into::<HtmlSelectElement>().value()).unwrap_or(what_if.rain);
+            Message::UpdateWhatIf(what_if)
+        });
+
+        html! {
+            <>
+                <li>
+                    <label>{ "Scenarios from " }
+                        <input type="date" value={dates::iso(self.what_if.from)} onchange={on_from} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Rain " }
+                        <select onchange={on_rain}>
+                            { for RainSource::ALL.into_iter().map(|source| html! {
+                                <option selected={source == self.what_if.rain} value={source.name()}>{ source.name() }</option>
+                            }) }
+                        </select>
+                    </label>
+                </li>
+                { for self.scenarios.iter().enumerate().map(|(index, scenario)| {
+                    let edit = |change: fn(&mut Scenario, &HtmlInputElement)| {
+                        let scenario = scenario.clone();
+                        ctx.link().callback(move |e: Event| {
+                            let mut scenario = scenario.clone();
+                            change(&mut scenario, &e.target_unchecked_into::<HtmlInputElement>());
+                            Message::UpdateScenario(index, scenario)
+                        })
+                    };
+                    let on_schedule = {
+                        let scenario = scenario.clone();
+                        ctx.link().callback(move |e: Event| {
+                            let mut scenario = scenario.clone();
+                            scenario.schedule = Schedule::from_name(&e.target_unchecked_into::<HtmlSelectElement>().value()).unwrap_or(scenario.schedule);
+                            Message::UpdateScenario(index, scenario)
+                        })
+                    };
+                    html! {
+                        <>
+                            <li>
+                                <input type="text" value={scenario.name.clone()} onchange={edit(|scenario, input| scenario.name = input.value())} />
+                                <select onchange={on_schedule}>
+                                    { for Schedule::ALL.into_iter().map(|schedule| html! {
+                                        <option selected={schedule == scenario.schedule} value={schedule.name()}>{ schedule.name() }</option>
+                                    }) }
+                                </select>
+                                <button onclick={ctx.link().callback(move |_| Message::RemoveScenario(index))}>{ "x" }</button>
+                            </li>
+                            if scenario.schedule == Schedule::Weekly {
+                                <li>
+                                    <input type="number" min="0" step="0.05" value={scenario.amount.to_string()}
+                                        onchange={edit(|scenario, input| scenario.amount = input.value().parse().unwrap_or(scenario.amount))} />
+                                    { " in on " }
+                                    { for scenario::WEEKDAYS.iter().enumerate().map(|(weekday, name)| {
+                                        let checked = scenario.weekdays[weekday];
+                                        let scenario = scenario.clone();
+                                        let on_day = ctx.link().callback(move |e: Event| {
+                                            let mut scenario = scenario.clone();
+                                            scenario.weekdays[weekday] = e.target_unchecked_into::<HtmlInputElement>().checked();
+                                            Message::UpdateScenario(index, scenario)
+                                        });
+                                        html! {
+                                            <label><input type="checkbox" {checked} onchange={on_day} />{ *name }</label>
+                                        }
+                                    }) }
+                                </li>
+                            }
+                        </>
+                    }
+                }) }
+                <li>
+                    <button onclick={ctx.link().callback(|_| Message::AddScenario)}>{ "Add scenario" }</button>
+                </li>
+            </>
+        }
+    }
+
+    // Inputs for the rainfall simulator
+    fn rain_settings(&self, ctx: &Context<Self>) -> Html {
+        let rain = self.rain;
+        let on_seed = ctx.link().callback(move |e: Event| {
+            let mut rain = rain;
+            rain.seed = e.target_unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(rain.seed);
+            Message::UpdateRain(rain)
+        });
+        let on_seasons = ctx.link().callback(move |e: Event| {
+            let mut rain = rain;
+            rain.seasons = e.target_unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(rain.seasons);
+            Message::UpdateRain(rain)
+        });
+
+        html! {
+            <>
+                <li>
+                    <label>{ "Seed " }
+                        <input type="number" min="0" step="1" value={self.rain.seed.to_string()} onchange={on_seed} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Seasons " }
+                        <input type="number" min="1" max="10000" step="100" value={self.rain.seasons.to_string()} onchange={on_seasons} />
+                    </label>
+                </li>
+            </>
+        }
+    }
+
+    // Inputs for the growing degree day calculation
+    fn gdd_settings(&self, ctx: &Context<Self>) -> Html {
+        let gdd = self.gdd;
+        let on_method = ctx.link().callback(move |e: Event| {
+            let mut gdd = gdd;
+            gdd.method = GddMethod::from_name(&e.target_unchecked_into::<HtmlSelectElement>().value()).unwrap_or(gdd.method);
+            Message::UpdateGdd(gdd)
+        });
+        let on_base = ctx.link().callback(move |e: Event| {
+            let mut gdd = gdd;
+            gdd.base = e.target_unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(gdd.base);
+            Message::UpdateGdd(gdd)
+        });
+        let on_cap = ctx.link().callback(move |e: Event| {
+            let mut gdd = gdd;
+            gdd.cap = e.target_unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(gdd.cap);
+            Message::UpdateGdd(gdd)
+        });
+
+        html! {
+            <>
+                <li>
+                    <label>{ "Method " }
+                        <select onchange={on_method}>
+                            { for GddMethod::ALL.iter().map(|method| html! {
+                                <option selected={*method == self.gdd.method}>{ method.name() }</option>
+                            }) }
+                        </select>
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Base (F) " }
+                        <input type="number" step="1" value={self.gdd.base.to_string()} onchange={on_base} />
+                    </label>
+                </li>
+                <li>
+                    <label>{ "Cap (F) " }
+                        <input type="number" step="1" value={self.gdd.cap.to_string()} onchange={on_cap} />
+                    </label>
+                </li>
+            </>
+        }
+    }
+
     async fn fetch_data(relative_url: String, team: String, column_name: usize, column_value: usize, caption: String) -> Message {
-        console::log!("Fetching data...");
-
-        // Fetch the file
-        let response = reqwest::get("https://k-state-drake-morgan.github.io/TAPS-2024-Hackathon/".to_string() + &relative_url)
-            .await
-            .map_err(|e| format!("Failed to fetch data: {}", e)).unwrap();
-            
-        let bytes = response.bytes()
-            .await
-            .map_err(|e| format!("Failed to read response bytes: {}", e)).unwrap();
-        
-        let bytes_wrapper = Cursor::new(bytes);
-        console::log!("Data shared to workbook...");
-
-        // Open the workbook
-        let mut workbook = open_workbook_auto_from_rs(bytes_wrapper)
-            .map_err(|e| format!("Failed to open workbook: {}", e)).unwrap();
-        
-        // Get the specified worksheet
-        let range = workbook.worksheet_range(&team).unwrap();
-
-        console::log!("Deserializing data...");
+        // Fetch the file and get the specified worksheet
+        let range = match data::fetch_sheet(&relative_url, &team).await {
+            Ok(range) => range,
+            Err(e) => {
+                console::error!(e);
+                return Message::None;
+            }
+        };
+
         let mut data = Vec::new();
 
         // Iterate through rows
         for row in range.rows() {
             if row.len() > column_value {
-                let name = row[column_name].clone();
-                let a: Option<f64> = match name {
-                    calamine::Data::Int(x) => Some(x as f64),
-                    calamine::Data::Float(x) => Some(x),
-                    calamine::Data::String(_x) => None,
-                    calamine::Data::Bool(_x) => None,
-                    calamine::Data::DateTime(excel_date_time) => Some(excel_date_time.as_f64()),
-                    calamine::Data::DateTimeIso(_x) => None,
-                    calamine::Data::DurationIso(_x) => None,
-                    calamine::Data::Error(_cell_error_type) => None,
-                    calamine::Data::Empty => None,
-                };
+                let a: Option<f64> = data::cell_f64(&row[column_name]);
                 let keyn = a.unwrap_or(0.0) as f32;
                 let value = calamine::DataType::get_float(&row[column_value]).unwrap_or(0.0) as f32;
                 if keyn == 0.0 && value == 0.0 {
@@ -451,11 +1641,1303 @@ impl App {
             }
         }
 
-        console::log!("Data retrieved successfully!");
-        console::log!(format!("{:?}", data));
-
         Message::MakePlot(caption, data)
     }
+
+    // Run the FAO-56 bucket model for a team and line it up against what their probe saw
+    async fn run_water_balance(team: u32, settings: &BalanceSettings, pivot: Pivot, logged: &[(f64, f64)]) -> Result<(Vec<DayBalance>, Vec<(f64, f64)>), String> {
+        let (inputs, observed, initial_depletion) = App::balance_inputs(team, settings, pivot, logged).await?;
+        let profile = soil::profile(&settings.plot_id);
+        Ok((water_balance::simulate(settings, &profile, &inputs, initial_depletion), observed))
+    }
+
+    // Days the water balance runs on, the probe's depletion and where the bucket starts
+    async fn balance_inputs(team: u32, settings: &BalanceSettings, pivot: Pivot, logged: &[(f64, f64)]) -> Result<(Vec<DayInput>, Vec<(f64, f64)>, f64), String> {
+        let sheet = data::sheet_name(team);
+        let range = data::fetch_sheet(data::ARABLE_FILE, &sheet).await?;
+        // Not every team has a probe, the model still runs without one
+        let probe = match data::fetch_sheet(data::AQUASPY_FILE, &sheet).await {
+            Ok(range) => Some(range),
+            Err(e) => {
+                console::log!(format!("No AquaSpy data for team #{team}: {e}"));
+                None
+            }
+        };
+        Ok(App::balance_days(settings, pivot, logged, &range, probe.as_ref()))
+    }
+
+    // `balance_inputs` from a team's Arable sheet and its AquaSpy sheet if it has one
+    fn balance_days(settings: &BalanceSettings, pivot: Pivot, logged: &[(f64, f64)], range: &Range<Data>,
+                    probe: Option<&Range<Data>>) -> (Vec<DayInput>, Vec<(f64, f64)>, f64) {
+        let profile = soil::profile(&settings.plot_id);
+        let irrigation = match probe {
+            _ if !logged.is_empty() => App::net_irrigation(logged, pivot.efficiency),
+            Some(probe) => App::irrigation_events(&App::detect_events(probe, Some(range)).1),
+            None => Vec::new(),
+        };
+        let mut inputs: Vec<DayInput> = data::table(range, arable::TIMESTAMP, &[settings.et_source.column().unwrap_or(arable::FIELD_ET), arable::PRECIPITATION])
+            .iter()
+            .map(|(day, values)| DayInput {
+                day: day.floor(),
+                et: values[0].unwrap_or(0.0),
+                rain: values[1].unwrap_or(0.0),
+                irrigation: App::irrigation_on(&irrigation, day.floor()),
+                kc: None,
+            })
+            .collect();
+        if settings.et_source == EtSource::Reference {
+            let weather = reference_et::weather(&data::table(range, arable::TIMESTAMP, &reference_et::COLUMNS));
+            for input in inputs.iter_mut() {
+                input.et = weather.iter()
+                    .find(|weather| weather.day == input.day)
+                    .map(|weather| reference_et::daily(weather, Surface::Short))
+                    .unwrap_or(0.0);
+            }
+        }
+
+        if settings.kc_source == KcSource::Ndvi {
+            let coefficients = settings.crop.coefficients();
+            let smoothed = ndvi::smooth(&App::ndvi(range), ndvi::WINDOW);
+            for input in inputs.iter_mut() {
+                input.kc = smoothed.iter()
+                    .find(|(day, _)| *day == input.day)
+                    .map(|(_, ndvi)| ndvi::kc(&coefficients, ndvi::canopy_cover(*ndvi)));
+            }
+        }
+
+        let moisture_columns: Vec<usize> = (aquaspy::MOISTURE..aquaspy::MOISTURE + aquaspy::DEPTHS.len()).collect();
+        let observed = match probe {
+            Some(probe) => {
+                let moisture = data::daily_mean(&data::table(probe, aquaspy::TIMESTAMP, &moisture_columns));
+                water_balance::observed_depletion(settings, &profile, &moisture)
+            },
+            None => Vec::new(),
+        };
+
+        // Start the bucket where the probe says it was, or full if we don't know
+        let start = inputs.first().map(|input| input.day).unwrap_or_default();
+        let initial_depletion = observed.iter().find(|(day, _)| *day == start).map(|(_, depletion)| *depletion).unwrap_or(0.0);
+
+        (inputs, observed, initial_depletion)
+    }
+
+    async fn fetch_water_balance(team: u32, settings: BalanceSettings, pivot: Pivot, logged: Vec<(f64, f64)>) -> Message {
+        let (balance, observed) = match App::run_water_balance(team, &settings, pivot, &logged).await {
+            Ok(result) => result,
+            Err(e) => {
+                console::error!(e);
+                return Message::None;
+            }
+        };
+
+        let mut series = vec![
+            Series::new("Simulated depletion", BLUE, balance.iter().map(|day| (day.day, day.depletion)).collect()),
+            Series::new("Readily available water", RGBColor(230, 120, 0), balance.iter().map(|day| (day.day, day.raw)).collect()),
+            Series::new("Total available water", RED, balance.iter().map(|day| (day.day, day.taw)).collect()),
+        ];
+        if !observed.is_empty() {
+            series.push(Series::new("Observed depletion (AquaSpy)", BLACK, observed));
+        }
+
+        // Teams with a probe get the rain and irrigation it saw marked
+        let markers = match App::wetting_events(team).await {
+            Ok((_, events)) => App::event_markers(&events),
+            Err(_) => Vec::new(),
+        };
+
+        Message::MakeChart(format!("Team #{team} root zone depletion (in) / time"), series, markers)
+    }
+
+    // Next week's plan from where the water balance leaves off, with the balance it came from
+    // and today's depletion
+    async fn plan_next_week(team: u32, settings: &BalanceSettings, pivot: Pivot, logged: &[(f64, f64)]) -> Result<(Vec<DayBalance>, f64, recommend::Recommendation), String> {
+        let (balance, observed) = App::run_water_balance(team, settings, pivot, logged).await?;
+        let Some(today) = balance.last().copied() else {
+            return Err(format!("No water balance for team #{team}"));
+        };
+
+        let year = dates::to_ymd(today.day).0;
+        let weeks = match data::fetch_sheet(rainfall::FILE, rainfall::SHEET).await {
+            Ok(range) => rainfall::weeks(&range, year),
+            Err(e) => {
+                console::error!(e);
+                Vec::new()
+            }
+        };
+
+        // The probe knows better than the model where the root zone is today
+        let depletion = observed.iter()
+            .find(|(day, _)| *day == today.day)
+            .map(|(_, depletion)| depletion.max(0.0))
+            .unwrap_or(today.depletion);
+        let last_week = &balance[balance.len().saturating_sub(7)..];
+        let outlook = Outlook {
+            today: today.day,
+            depletion,
+            taw: today.taw,
+            raw: today.raw,
+            et: last_week.iter().map(|day| day.etc).sum::<f64>() / last_week.len() as f64,
+        };
+        let recommendation = recommend::recommend(&outlook, &pivot, &weeks);
+        Ok((balance, depletion, recommendation))
+    }
+
+    // Plan the next week of irrigation from where the water balance leaves off
+    async fn fetch_recommendation(team: u32, settings: BalanceSettings, pivot: Pivot, logged: Vec<(f64, f64)>) -> Vec<Message> {
+        let (balance, depletion, recommendation) = match App::plan_next_week(team, &settings, pivot, &logged).await {
+            Ok(result) => result,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let today = balance[balance.len() - 1];
+
+        let history = &balance[balance.len().saturating_sub(14)..];
+        let planned = std::iter::once((today.day, depletion)).chain(recommendation.plan.iter().map(|day| (day.day, day.depletion))).collect();
+        let unirrigated = std::iter::once((today.day, depletion)).chain(recommendation.plan.iter().map(|day| (day.day, day.unirrigated))).collect();
+        let end = recommendation.plan.last().map(|day| day.day).unwrap_or(today.day);
+        let series = vec![
+            Series::new("Simulated depletion", BLUE, history.iter().map(|day| (day.day, day.depletion)).collect()),
+            Series::new("Planned depletion", GREEN, planned),
+            Series::new("Depletion without irrigation", RED, unirrigated),
+            Series::new("Readily available water", RGBColor(230, 120, 0), vec![(history[0].day, today.raw), (end, today.raw)]),
+        ];
+
+        vec![
+            Message::MakeChart(format!("Team #{team} irrigation plan, depletion (in) / time"), series, Vec::new()),
+            Message::ShowNotes(format!("Team #{team} irrigation plan"), recommendation.reasons),
+        ]
+    }
+
+    // Columns `accumulate_gdd` wants from the Arable sheet, in order
+    const GDD_COLUMNS: [usize; 4] = [arable::MAX_TEMP, arable::MIN_TEMP, arable::GDD, arable::ACCUMULATED_GDD];
+
+    // Our accumulated GDD from Arable rows (GDD_COLUMNS), and what was carried over when the
+    // crop went in before the sensor did (Arable's count up to the first day)
+    fn accumulate_gdd(rows: &[(f64, Vec<Option<f64>>)], gdd: GddSettings, planting: f64) -> (Vec<(f64, f64)>, Option<f64>) {
+        let temperatures: Vec<(f64, f64, f64)> = rows.iter()
+            .filter_map(|(day, values)| Some((day.floor(), values[0]?, values[1]?)))
+            .collect();
+        let first = temperatures.first().map(|(day, _, _)| *day).unwrap_or(planting);
+        let carried = (planting < first).then(|| {
+            rows.iter()
+                .find(|(day, _)| day.floor() == first)
+                .and_then(|(_, values)| Some(values[3]? - values[2]?))
+                .unwrap_or(0.0)
+        });
+        (gdd.accumulate(&temperatures, planting, carried.unwrap_or(0.0)), carried)
+    }
+
+    // Crop stages for the strip under the charts
+    //
+    // Every plot is in the same field, so one Arable's temperatures do for all of them.
+    async fn fetch_stages(crop: Crop, planting: f64, gdd: GddSettings) -> Message {
+        match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(data::WEATHER_TEAM)).await {
+            Ok(range) => {
+                let rows = data::table(&range, arable::TIMESTAMP, &App::GDD_COLUMNS);
+                Message::UpdateStages(crop.stage_timeline(&App::accumulate_gdd(&rows, gdd, planting).0))
+            },
+            Err(e) => {
+                console::error!(e);
+                Message::None
+            }
+        }
+    }
+
+    // Growing degree days worked out from Arable's temperatures, next to Arable's own numbers
+    async fn fetch_gdd(team: u32, gdd: GddSettings, planting: f64) -> Vec<Message> {
+        let rows = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(team)).await {
+            Ok(range) => data::table(&range, arable::TIMESTAMP, &App::GDD_COLUMNS),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let temperatures: Vec<(f64, f64, f64)> = rows.iter()
+            .filter_map(|(day, values)| Some((day.floor(), values[0]?, values[1]?)))
+            .collect();
+        let vendor_daily: Vec<(f64, f64)> = rows.iter().filter_map(|(day, values)| Some((day.floor(), values[2]?))).collect();
+        let vendor: Vec<(f64, f64)> = rows.iter().filter_map(|(day, values)| Some((day.floor(), values[3]?))).collect();
+        let Some(&(first, _, _)) = temperatures.first() else {
+            return Vec::new();
+        };
+
+        let (ours, carried) = App::accumulate_gdd(&rows, gdd, planting);
+        let mut notes = vec![match carried {
+            Some(before) => format!("Planted {} but temperatures start {}, starting from Arable's {:.0} GDD built up before that.", dates::label(planting), dates::label(first), before),
+            None => format!("Accumulated from planting on {}.", dates::label(planting)),
+        }];
+
+        // How close every method gets to Arable's daily numbers with the same base and cap
+        for method in GddMethod::ALL {
+            let settings = GddSettings { method, ..gdd };
+            let differences: Vec<f64> = temperatures.iter()
+                .filter_map(|&(day, max, min)| {
+                    let vendor = vendor_daily.iter().find(|(vendor_day, _)| *vendor_day == day)?.1;
+                    Some(settings.daily(max, min) - vendor)
+                })
+                .collect();
+            if differences.is_empty() {
+                continue;
+            }
+            let mean_absolute = differences.iter().map(|difference| difference.abs()).sum::<f64>() / differences.len() as f64;
+            notes.push(format!("{}: off from Arable by {:.1} GDD a day on average, {:+.0} over the season.", method.name(), mean_absolute, differences.iter().sum::<f64>()));
+        }
+        if let (Some(ours), Some(vendor)) = (ours.last(), vendor.last()) {
+            notes.push(format!("{} ({:.0}/{:.0} F): {:.0} GDD by {}, Arable says {:.0}.", gdd.method.name(), gdd.base, gdd.cap, ours.1, dates::label(ours.0), vendor.1));
+        }
+
+        let series = vec![
+            Series::new(format!("{} GDD", gdd.method.name()), BLUE, ours),
+            Series::new("Arable accumulated GDD", RED, vendor),
+        ];
+        vec![
+            Message::MakeChart(format!("Team #{team} accumulated growing degree days / time"), series, Vec::new()),
+            Message::ShowNotes(format!("Team #{team} growing degree days"), notes),
+        ]
+    }
+
+    // ASCE ETo / ETr from the Arable's own weather next to the ET it reports, the ratio of the
+    // two is the crop coefficient the field is actually showing
+    async fn fetch_reference_et(team: u32) -> Vec<Message> {
+        let range = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(team)).await {
+            Ok(range) => range,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let weather = reference_et::weather(&data::table(&range, arable::TIMESTAMP, &reference_et::COLUMNS));
+        let arable_et: Vec<(f64, Vec<Option<f64>>)> = data::table(&range, arable::TIMESTAMP, &[arable::FIELD_ET, arable::CANOPY_ET]);
+
+        // (day, ETo, ETr, field ET, canopy ET) for days with everything
+        let days: Vec<(f64, f64, f64, f64, f64)> = weather.iter()
+            .filter_map(|weather| {
+                let (_, values) = arable_et.iter().find(|(day, _)| day.floor() == weather.day)?;
+                Some((weather.day, reference_et::daily(weather, Surface::Short), reference_et::daily(weather, Surface::Tall), values[0]?, values[1]?))
+            })
+            .collect();
+        if days.is_empty() {
+            return vec![Message::ShowNotes(format!("Team #{team} reference ET"), vec!["No days with all the weather readings ETo needs.".to_string()])];
+        }
+
+        // Summing over a week keeps a cloudy day with next to no ETo from blowing the ratio up
+        let ratio = |days: &[(f64, f64, f64, f64, f64)], pick: fn(&(f64, f64, f64, f64, f64)) -> f64| {
+            let reference: f64 = days.iter().map(|day| day.1).sum();
+            if reference > 0.0 { days.iter().map(pick).sum::<f64>() / reference } else { 0.0 }
+        };
+        let mut notes = vec![
+            format!("Season: ETo {:.1} in, ETr {:.1} in, Arable field ET {:.1} in, canopy ET {:.1} in.",
+                days.iter().map(|day| day.1).sum::<f64>(), days.iter().map(|day| day.2).sum::<f64>(),
+                days.iter().map(|day| day.3).sum::<f64>(), days.iter().map(|day| day.4).sum::<f64>()),
+            format!("Empirical Kc over the season: {:.2} from field ET, {:.2} from canopy ET (both against ETo).", ratio(&days, |day| day.3), ratio(&days, |day| day.4)),
+        ];
+        for week in days.chunks(7) {
+            notes.push(format!("Week of {}: Kc {:.2} field, {:.2} canopy.", dates::label(week[0].0), ratio(week, |day| day.3), ratio(week, |day| day.4)));
+        }
+
+        let series = vec![
+            Series::new(Surface::Short.name(), BLUE, days.iter().map(|day| (day.0, day.1)).collect()),
+            Series::new(Surface::Tall.name(), RGBColor(230, 120, 0), days.iter().map(|day| (day.0, day.2)).collect()),
+            Series::new("Arable field ET", BLACK, days.iter().map(|day| (day.0, day.3)).collect()),
+            Series::new("Arable canopy ET", GREEN, days.iter().map(|day| (day.0, day.4)).collect()),
+        ];
+        vec![
+            Message::MakeChart(format!("Team #{team} reference and measured ET (in) / time"), series, Vec::new()),
+            Message::ShowNotes(format!("Team #{team} reference ET"), notes),
+        ]
+    }
+
+    // Daily NDVI readings from an Arable sheet
+    fn ndvi(range: &Range<Data>) -> Vec<(f64, f64)> {
+        data::table(range, arable::TIMESTAMP, &[arable::NDVI])
+            .iter()
+            .filter_map(|(day, values)| Some((day.floor(), values[0]?)))
+            .collect()
+    }
+
+    // NDVI smoothed, turned into canopy cover and then a crop coefficient, against the FAO
+    // curve the water balance uses otherwise and Arable's own coefficient
+    async fn fetch_crop_coefficient(team: u32, settings: BalanceSettings) -> Vec<Message> {
+        let range = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(team)).await {
+            Ok(range) => range,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let coefficients = settings.crop.coefficients();
+        let raw = App::ndvi(&range);
+        let smoothed = ndvi::smooth(&raw, ndvi::WINDOW);
+        let cover: Vec<(f64, f64)> = smoothed.iter().map(|&(day, ndvi)| (day, ndvi::canopy_cover(ndvi))).collect();
+        let kc: Vec<(f64, f64)> = cover.iter().map(|&(day, cover)| (day, ndvi::kc(&coefficients, cover))).collect();
+        let fao: Vec<(f64, f64)> = kc.iter().map(|&(day, _)| (day, coefficients.kc(day - settings.planting))).collect();
+        let vendor: Vec<(f64, f64)> = data::table(&range, arable::TIMESTAMP, &[arable::CROP_COEFFICIENT])
+            .iter()
+            .filter_map(|(day, values)| Some((day.floor(), values[0]?)))
+            .collect();
+
+        let mut notes = Vec::new();
+        if let Some(&(day, peak)) = smoothed.iter().max_by(|a, b| a.1.total_cmp(&b.1)) {
+            notes.push(format!("NDVI peaked at {:.2} ({:.0}% cover) around {}.", peak, ndvi::canopy_cover(peak) * 100.0, dates::label(day)));
+        }
+        if let Some(&(day, _)) = cover.iter().find(|(_, cover)| *cover >= 0.8) {
+            notes.push(format!("Canopy reached 80% cover on {}, the FAO curve gets to full Kc {} days after planting.", dates::label(day), coefficients.stage_days[0] + coefficients.stage_days[1]));
+        }
+        let mean = |points: &[(f64, f64)]| points.iter().map(|(_, value)| value).sum::<f64>() / points.len().max(1) as f64;
+        notes.push(format!("Average Kc over these days: {:.2} from NDVI, {:.2} from the {} FAO curve (planted {}), {:.2} from Arable.",
+            mean(&kc), mean(&fao), settings.crop.name().to_lowercase(), dates::label(settings.planting), mean(&vendor)));
+        notes.push(format!("NDVI is averaged over {} days to take the noise out, pick \"{}\" in the water balance settings to use this Kc.", ndvi::WINDOW, KcSource::Ndvi.name()));
+
+        let series = vec![
+            Series::new("NDVI", RGBColor(120, 120, 120), raw),
+            Series::new("Smoothed NDVI", GREEN, smoothed),
+            Series::new("Canopy cover", BLUE, cover),
+            Series::new("NDVI Kc", BLACK, kc),
+            Series::new("FAO-56 Kc", RED, fao),
+            Series::new("Arable Kc", RGBColor(230, 120, 0), vendor),
+        ];
+        vec![
+            Message::MakeChart(format!("Team #{team} NDVI and crop coefficient / time"), series, Vec::new()),
+            Message::ShowNotes(format!("Team #{team} crop coefficient"), notes),
+        ]
+    }
+
+    // Weekly rainfall drawn from the fitted distributions, next to the file's own statistics
+    async fn fetch_rainfall_simulation(rain: SimulationSettings, year: i64) -> Vec<Message> {
+        let weeks = match data::fetch_sheet(rainfall::FILE, rainfall::SHEET).await {
+            Ok(range) => rainfall::weeks(&range, year),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let models: Vec<rain_generator::WeekModel> = weeks.iter().map(rain_generator::fit).collect();
+        let seasons = rain_generator::seasons(&models, rain.seed, rain.seasons.max(1));
+        let week_draws = |index: usize| seasons.iter().map(|season| season[index]).collect::<Vec<f64>>();
+        let simulated = |percent: f64| models.iter().enumerate()
+            .map(|(index, model)| (model.start, rain_generator::percentile(&week_draws(index), percent)))
+            .collect::<Vec<(f64, f64)>>();
+
+        let totals: Vec<f64> = seasons.iter().map(|season| season.iter().sum()).collect();
+        let mut notes = vec![
+            format!("{} seasons from seed {}, the same seed always draws the same seasons.", seasons.len(), rain.seed),
+            format!("Season total ({} weeks): P10 {:.1} in, P50 {:.1} in, P90 {:.1} in.", weeks.len(),
+                rain_generator::percentile(&totals, 10.0), rain_generator::percentile(&totals, 50.0), rain_generator::percentile(&totals, 90.0)),
+        ];
+        for (index, (week, model)) in weeks.iter().zip(&models).enumerate() {
+            let draws = week_draws(index);
+            let dry = draws.iter().filter(|amount| **amount == 0.0).count() as f64 / draws.len() as f64;
+            notes.push(format!("{}: {:.0}% dry (file {:.0}%), median {:.2} in (file {:.2}), wet weeks ln-normal mu {:.2} sigma {:.2}.",
+                week.date_range, dry * 100.0, week.proportion_zero * 100.0, rain_generator::percentile(&draws, 50.0), week.median, model.mu, model.sigma));
+        }
+
+        let file = |pick: fn(&rainfall::WeeklyRain) -> f64| weeks.iter().map(|week| (week.start, pick(week))).collect::<Vec<(f64, f64)>>();
+        let series = vec![
+            Series::new("Simulated P10", RGBColor(120, 120, 220), simulated(10.0)),
+            Series::new("Simulated P50", BLUE, simulated(50.0)),
+            Series::new("Simulated P90", RGBColor(0, 0, 120), simulated(90.0)),
+            Series::new("File Q1", RGBColor(230, 120, 0), file(|week| week.q1)),
+            Series::new("File median", RED, file(|week| week.median)),
+            Series::new("File Q3", RGBColor(150, 0, 0), file(|week| week.q3)),
+            Series::new("One simulated season", BLACK, models.iter().zip(&seasons[0]).map(|(model, amount)| (model.start, *amount)).collect()),
+        ];
+        vec![
+            Message::MakeChart("Simulated weekly rainfall (in) / week".to_string(), series, Vec::new()),
+            Message::ShowNotes("Rainfall simulator".to_string(), notes),
+        ]
+    }
+
+    // Every past year's weekly rain side by side, with this season's (from the Arable) on top
+    async fn fetch_rainfall_history() -> Vec<Message> {
+        let totals = match data::fetch_sheet(history::FILE, history::SHEET).await {
+            Ok(range) => history::totals(&range),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let rain: Vec<(f64, f64)> = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(data::WEATHER_TEAM)).await {
+            Ok(range) => data::table(&range, arable::TIMESTAMP, &[arable::PRECIPITATION])
+                .iter()
+                .filter_map(|(day, values)| Some((day.floor(), values[0]?)))
+                .collect(),
+            Err(e) => {
+                console::error!(e);
+                Vec::new()
+            }
+        };
+        let season = rain.first().map(|(day, _)| dates::to_ymd(*day).0).unwrap_or(2024);
+        let names = history::week_names(&totals, season);
+        let years = history::years(&totals);
+
+        // Older years lighter, recent ones darker
+        let bars: Vec<Series> = years.iter().enumerate()
+            .map(|(index, &year)| {
+                let shade = 200 - (index * 170 / years.len().max(1)) as u8;
+                let label = if index == 0 || index + 1 == years.len() { year.to_string() } else { String::new() };
+                let points = names.iter().enumerate()
+                    .filter_map(|(group, name)| Some((group as f64, history::total(&totals, year, name)?)))
+                    .collect();
+                Series::new(label, RGBColor(shade / 2, shade, 255), points)
+            })
+            .collect();
+
+        let mut notes = vec![format!("{} years of history ({} - {}), {} from the Arable rain gauge.", years.len(),
+            years.first().copied().unwrap_or_default(), years.last().copied().unwrap_or_default(), season)];
+        let mut observed = Vec::new();
+        for (group, name) in names.iter().enumerate() {
+            let Some((start, end)) = rainfall::parse_week(name, season) else { continue };
+            let week: Vec<f64> = rain.iter().filter(|(day, _)| start <= *day && *day <= end).map(|(_, amount)| *amount).collect();
+            if week.is_empty() {
+                notes.push(format!("{name}: no readings this season."));
+                continue;
+            }
+            let total: f64 = week.iter().sum();
+            let (drier, count) = history::rank(&totals, name, total);
+            let partial = if week.len() < 7 { format!(" (only {} days of readings)", week.len()) } else { String::new() };
+            notes.push(format!("{name}: {total:.2} in{partial}, wetter than {drier} of {count} past years."));
+            observed.push((group as f64, total));
+        }
+        let markers = vec![Series::new(format!("{season} observed"), RED, observed)];
+
+        vec![
+            Message::MakeBars("Weekly rainfall (in) by year".to_string(), names, bars, markers),
+            Message::ShowNotes("Rainfall history".to_string(), notes),
+        ]
+    }
+
+    // Irrigation still needed to the end of September, over many simulated rainfall seasons
+    async fn fetch_water_need(team: u32, settings: BalanceSettings, pivot: Pivot, rain: SimulationSettings, as_of: Option<f64>, logged: Vec<(f64, f64)>) -> Vec<Message> {
+        let (mut balance, observed) = match App::run_water_balance(team, &settings, pivot, &logged).await {
+            Ok(result) => result,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        if let Some(as_of) = as_of {
+            balance.retain(|day| day.day <= as_of);
+        }
+        let Some(today) = balance.last().copied() else {
+            return vec![Message::ShowNotes(format!("Team #{team} water still needed"), vec!["No water balance on or before that day.".to_string()])];
+        };
+
+        let models: Vec<rain_generator::WeekModel> = match data::fetch_sheet(rainfall::FILE, rainfall::SHEET).await {
+            Ok(range) => rainfall::weeks(&range, dates::to_ymd(today.day).0).iter().map(rain_generator::fit).collect(),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+
+        // The probe knows better than the model where the root zone is today, demand carries
+        // on like the last week
+        let depletion = observed.iter()
+            .find(|(day, _)| *day == today.day)
+            .map(|(_, depletion)| depletion.max(0.0))
+            .unwrap_or(today.depletion);
+        let last_week: Vec<f64> = balance[balance.len().saturating_sub(7)..].iter()
+            .filter(|day| day.kc > 0.0)
+            .map(|day| day.etc / day.kc)
+            .collect();
+        let et = last_week.iter().sum::<f64>() / last_week.len().max(1) as f64;
+        let start = projection::Start { today: today.day, depletion, et };
+
+        let profile = soil::profile(&settings.plot_id);
+        let seasons = projection::seasons(&settings, &profile, &start, &models, rain.seed, rain.seasons.max(1), pivot.efficiency);
+        let [p10, p50, p90] = [10.0, 50.0, 90.0].map(|percent| projection::fan(&seasons, percent));
+        let end = |fan: &[(f64, f64)]| fan.last().map(|(_, total)| *total).unwrap_or(0.0);
+
+        let notes = vec![
+            format!("From {} to {}, starting {:.2} in below field capacity with {:.2} in/day of ET before Kc.",
+                dates::label(today.day), dates::label(projection::season_end(today.day)), depletion, et),
+            format!("Still needed (gross at {:.0}% efficiency): {:.1} in in a wet season (P10), {:.1} in typically (P50), {:.1} in in a dry one (P90).",
+                pivot.efficiency * 100.0, end(&p10), end(&p50), end(&p90)),
+            format!("Holding back {:.1} in of allocation covers 9 seasons in 10.", end(&p90)),
+            format!("{} rainfall seasons from seed {}, irrigating whenever depletion would pass readily available water.", seasons.len(), rain.seed),
+        ];
+        let series = vec![
+            Series::new("P10 (wet)", RGBColor(120, 120, 220), p10),
+            Series::new("P50", BLUE, p50),
+            Series::new("P90 (dry)", RGBColor(0, 0, 120), p90),
+        ];
+        vec![
+            Message::MakeChart(format!("Team #{team} irrigation still needed (in) / time"), series, Vec::new()),
+            Message::ShowNotes(format!("Team #{team} water still needed"), notes),
+        ]
+    }
+
+    // A team's AquaSpy readings (as recorded, not averaged) and the wetting events in them
+    async fn wetting_events(team: u32) -> Result<(Vec<(f64, Vec<Option<f64>>)>, Vec<events::WettingEvent>), String> {
+        let probe = data::fetch_sheet(data::AQUASPY_FILE, &data::sheet_name(team)).await?;
+        let weather = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(data::arable_team(team))).await {
+            Ok(range) => Some(range),
+            Err(e) => {
+                console::error!(e);
+                None
+            }
+        };
+        Ok(App::detect_events(&probe, weather.as_ref()))
+    }
+
+    // `wetting_events` from a team's AquaSpy sheet and the Arable sheet its rain comes from
+    #[allow(clippy::type_complexity)]
+    fn detect_events(probe: &Range<Data>, weather: Option<&Range<Data>>) -> (Vec<(f64, Vec<Option<f64>>)>, Vec<events::WettingEvent>) {
+        let moisture_columns: Vec<usize> = (aquaspy::MOISTURE..aquaspy::MOISTURE + aquaspy::DEPTHS.len()).collect();
+        let mut moisture = data::table(probe, aquaspy::TIMESTAMP, &moisture_columns);
+        moisture.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let rain: Vec<(f64, f64)> = weather
+            .map(|range| data::table(range, arable::TIMESTAMP, &[arable::PRECIPITATION])
+                .iter()
+                .filter_map(|(day, values)| Some((day.floor(), values[0]?)))
+                .collect())
+            .unwrap_or_default();
+        let events = events::detect(&moisture, &rain);
+        (moisture, events)
+    }
+
+    fn event_markers(events: &[events::WettingEvent]) -> Vec<Marker> {
+        events.iter()
+            .map(|event| {
+                let color = match event.kind {
+                    events::EventKind::Rain => BLUE,
+                    events::EventKind::Irrigation => RED,
+                };
+                Marker::new(event.start, event.label(), color)
+            })
+            .collect()
+    }
+
+    // Shallow, middle and deep moisture with every detected event marked
+    async fn fetch_wetting_events(team: u32) -> Vec<Message> {
+        let (moisture, events) = match App::wetting_events(team).await {
+            Ok(result) => result,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+
+        // Average of the sensors in a range of depths, per day to keep the chart readable
+        let daily = data::daily_mean(&moisture);
+        let band = |top: f64, bottom: f64| daily.iter()
+            .filter_map(|(day, readings)| {
+                let values: Vec<f64> = aquaspy::DEPTHS.iter().zip(readings)
+                    .filter(|(depth, _)| top <= **depth && **depth <= bottom)
+                    .filter_map(|(_, reading)| *reading)
+                    .collect();
+                (!values.is_empty()).then(|| (*day, values.iter().sum::<f64>() / values.len() as f64))
+            })
+            .collect::<Vec<(f64, f64)>>();
+
+        let irrigation: Vec<&events::WettingEvent> = events.iter().filter(|event| event.kind == events::EventKind::Irrigation).collect();
+        let mut notes = vec![format!("{} events, {} put down to irrigation adding up to {:.2} in stored in the profile.",
+            events.len(), irrigation.len(), irrigation.iter().map(|event| event.applied).sum::<f64>())];
+        notes.extend(events.iter().map(events::describe));
+        if events.is_empty() {
+            notes.push("No quick rises at the shallow sensors.".to_string());
+        }
+
+        let series = vec![
+            Series::new("4 - 12 in moisture (%)", GREEN, band(4.0, 12.0)),
+            Series::new("16 - 32 in moisture (%)", RGBColor(230, 120, 0), band(16.0, 32.0)),
+            Series::new("36 - 48 in moisture (%)", BLACK, band(36.0, 48.0)),
+        ];
+        vec![
+            Message::MakeChart(format!("Team #{team} moisture and wetting events / time"), series, App::event_markers(&events)),
+            Message::ShowNotes(format!("Team #{team} wetting events"), notes),
+        ]
+    }
+
+    // Wetting front depth and water lost below the roots, per event and added up over the season
+    async fn fetch_deep_percolation(team: u32) -> Vec<Message> {
+        let (moisture, events) = match App::wetting_events(team).await {
+            Ok(result) => result,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let fronts = percolation::fronts(&moisture, &events);
+
+        let mut total = 0.0;
+        let cumulative: Vec<(f64, f64)> = fronts.iter()
+            .map(|front| {
+                total += front.lost;
+                (front.event.start, total)
+            })
+            .collect();
+        let lost_to = |kind: events::EventKind| fronts.iter().filter(|front| front.event.kind == kind).map(|front| front.lost).sum::<f64>();
+        let deep = fronts.iter().filter(|front| front.depth.map(|depth| depth >= percolation::DEEP).unwrap_or(false)).count();
+
+        let mut notes = vec![
+            format!("{:.2} in lost below {} in over the season, {:.2} in after irrigation and {:.2} in after rain.",
+                total, percolation::DEEP, lost_to(events::EventKind::Irrigation), lost_to(events::EventKind::Rain)),
+            format!("{} of {} events wetted {} in or deeper.", deep, fronts.len(), percolation::DEEP),
+        ];
+        if fronts.iter().any(|front| front.below_probe && front.event.kind == events::EventKind::Irrigation) {
+            notes.push("Some irrigations pushed water past the bottom of the probe, smaller more frequent passes would keep it in the root zone.".to_string());
+        }
+        notes.extend(fronts.iter().map(percolation::describe));
+
+        let markers = fronts.iter()
+            .map(|front| {
+                let color = if front.depth.map(|depth| depth >= percolation::DEEP).unwrap_or(false) { RED } else { BLUE };
+                let depth = front.depth.map(|depth| format!("{depth} in")).unwrap_or_else(|| "shallow".to_string());
+                Marker::new(front.event.start, format!("{} to {}", front.event.kind.name(), depth), color)
+            })
+            .collect();
+        let series = vec![
+            Series::new("Lost per event (in)", RGBColor(230, 120, 0), fronts.iter().map(|front| (front.event.start, front.lost)).collect()),
+            Series::new("Lost over the season (in)", RED, cumulative),
+        ];
+        vec![
+            Message::MakeChart(format!("Team #{team} deep percolation (in) / time"), series, markers),
+            Message::ShowNotes(format!("Team #{team} deep percolation"), notes),
+        ]
+    }
+
+    // Daily water use read off the probe drying out, next to what the Arable says
+    async fn fetch_drawdown(team: u32) -> Vec<Message> {
+        let (moisture, events) = match App::wetting_events(team).await {
+            Ok(result) => result,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let used = drawdown::daily_use(&moisture, &events);
+
+        let arable_team = data::arable_team(team);
+        let arable_et: Vec<(f64, Vec<Option<f64>>)> = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(arable_team)).await {
+            Ok(range) => data::table(&range, arable::TIMESTAMP, &[arable::FIELD_ET, arable::CANOPY_ET]),
+            Err(e) => {
+                console::error!(e);
+                Vec::new()
+            }
+        };
+        let arable = |column: usize| arable_et.iter()
+            .filter_map(|(day, values)| Some((day.floor(), values[column]?)))
+            .collect::<Vec<(f64, f64)>>();
+        let (field, canopy) = (arable(0), arable(1));
+
+        // Only days both have
+        let paired: Vec<(f64, f64)> = used.iter()
+            .filter_map(|(day, used)| Some((*used, field.iter().find(|(other, _)| other == day)?.1)))
+            .collect();
+        let days = match (moisture.first(), moisture.last()) {
+            (Some(first), Some(last)) => (last.0.floor() - first.0.floor()) as usize,
+            _ => 0,
+        };
+        let mut notes = vec![format!("{} days of drawdown, {} left out around {} wetting events or missing readings.",
+            used.len(), days.saturating_sub(used.len()), events.len())];
+        if !paired.is_empty() {
+            let probe: f64 = paired.iter().map(|pair| pair.0).sum();
+            let vendor: f64 = paired.iter().map(|pair| pair.1).sum();
+            let mean_absolute = paired.iter().map(|pair| (pair.0 - pair.1).abs()).sum::<f64>() / paired.len() as f64;
+            notes.push(format!("Over the {} days both have: {:.2} in from drawdown, {:.2} in Arable field ET (team #{arable_team}), ratio {:.2}.",
+                paired.len(), probe, vendor, if vendor > 0.0 { probe / vendor } else { 0.0 }));
+            notes.push(format!("Off by {:.2} in/day on average.", mean_absolute));
+        }
+        notes.push("Drawdown only sees the top 48 in and can't tell crop use from slow drainage, so it reads high while a wet profile is still draining.".to_string());
+
+        let series = vec![
+            Series::new("Drawdown water use", BLACK, used),
+            Series::new(format!("Arable #{arable_team} field ET"), BLUE, field),
+            Series::new(format!("Arable #{arable_team} canopy ET"), GREEN, canopy),
+        ];
+        vec![
+            Message::MakeChart(format!("Team #{team} daily water use (in) / time"), series, App::event_markers(&events)),
+            Message::ShowNotes(format!("Team #{team} drawdown water use"), notes),
+        ]
+    }
+
+    // How deep the roots are working, from daytime-only drawdown at each sensor, against the
+    // depth the water balance assumes
+    async fn fetch_root_depth(team: u32, settings: BalanceSettings) -> Vec<Message> {
+        let (moisture, events) = match App::wetting_events(team).await {
+            Ok(result) => result,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let active = roots::active_depths(&moisture, &events);
+        let effective = roots::effective_depth(&active);
+        let coefficients = settings.crop.coefficients();
+        let model: Vec<(f64, f64)> = effective.iter().map(|(day, _)| (*day, coefficients.root_depth(day - settings.planting))).collect();
+
+        let mut notes = Vec::new();
+        if let Some(&(day, depth)) = effective.last() {
+            notes.push(format!("Roots are working down to {} in as of {} (deepest sensor with daytime-only drawdown in the last {} days).", depth, dates::label(day), roots::WINDOW));
+            let model_depth = coefficients.root_depth(day - settings.planting);
+            notes.push(format!("The water balance assumes {:.0} in for {} planted {}.", model_depth, settings.crop.name().to_lowercase(), dates::label(settings.planting)));
+            if depth > 0.0 {
+                notes.push(format!("Refill to about {} in, water put below that won't be used until the roots get there.", depth));
+            }
+        }
+        if let Some(&(day, depth)) = effective.iter().max_by(|a, b| a.1.total_cmp(&b.1)) {
+            notes.push(format!("Deepest over the season: {} in, first on {}.", depth, dates::label(day)));
+        }
+        notes.push("Event days and the day after are left out, drainage looks like uptake then.".to_string());
+
+        let series = vec![
+            Series::new("Deepest active sensor (in)", RGBColor(120, 120, 120), roots::deepest(&active)),
+            Series::new("Effective rooting depth (in)", GREEN, effective),
+            Series::new("Water balance root depth (in)", BLUE, model),
+        ];
+        vec![
+            Message::MakeChart(format!("Team #{team} effective rooting depth (in) / time"), series, App::event_markers(&events)),
+            Message::ShowNotes(format!("Team #{team} rooting depth"), notes),
+        ]
+    }
+
+    // Pore-water EC by depth, so salt and fertilizer moving through shows up apart from moisture
+    async fn fetch_pore_ec(team: u32) -> Vec<Message> {
+        let sensors = aquaspy::DEPTHS.len();
+        let columns: Vec<usize> = [aquaspy::MOISTURE, aquaspy::EC, aquaspy::TEMPERATURE].iter()
+            .flat_map(|&first| first..first + sensors)
+            .collect();
+        let mut rows = match data::fetch_sheet(data::AQUASPY_FILE, &data::sheet_name(team)).await {
+            Ok(range) => data::table(&range, aquaspy::TIMESTAMP, &columns),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let pore: Vec<(f64, Vec<Option<f64>>)> = rows.iter()
+            .map(|(time, values)| (*time, salinity::pore_series(&values[..sensors], &values[sensors..2 * sensors], &values[2 * sensors..])))
+            .collect();
+        let daily = data::daily_mean(&pore);
+
+        // Shallow sensors light, deep ones dark
+        let series: Vec<Series> = aquaspy::DEPTHS.iter().enumerate()
+            .map(|(sensor, depth)| {
+                let shade = 220 - (sensor * 200 / sensors) as u8;
+                let points = daily.iter().filter_map(|(day, values)| Some((*day, values[sensor]?))).collect();
+                Series::new(format!("{depth} in"), RGBColor(shade / 3, shade, shade / 2), points)
+            })
+            .collect();
+
+        let mut notes = vec!["EC with the moisture taken out (Hilhorst) and brought to 25 C, in the probe's EC units.".to_string()];
+        // Start of the season against the end, a week of each
+        let week = |values: &[(f64, Vec<Option<f64>>)], sensor: usize| {
+            let found: Vec<f64> = values.iter().filter_map(|(_, values)| values[sensor]).collect();
+            (!found.is_empty()).then(|| found.iter().sum::<f64>() / found.len() as f64)
+        };
+        let first_week = &daily[..daily.len().min(7)];
+        let last_week = &daily[daily.len().saturating_sub(7)..];
+        for (sensor, depth) in aquaspy::DEPTHS.iter().enumerate() {
+            if let (Some(start), Some(end)) = (week(first_week, sensor), week(last_week, sensor)) {
+                notes.push(format!("{depth} in: {:.2} the first week, {:.2} the last ({:+.0}%).", start, end, if start > 0.0 { (end / start - 1.0) * 100.0 } else { 0.0 }));
+            }
+        }
+        for (day, sensor, jump) in salinity::pulses(&daily, 0.25) {
+            notes.push(format!("Pulse at {} in on {}, {:.0}% over the week before.", aquaspy::DEPTHS[sensor], dates::label(day), jump * 100.0));
+        }
+
+        vec![
+            Message::MakeChart(format!("Team #{team} pore-water EC at 25 C / time"), series, Vec::new()),
+            Message::ShowNotes(format!("Team #{team} pore-water EC"), notes),
+        ]
+    }
+
+    // Latest day's temperature profile against the season's, with planting and heat alerts
+    async fn fetch_soil_temperature(team: u32) -> Vec<Message> {
+        let columns: Vec<usize> = (aquaspy::TEMPERATURE..aquaspy::TEMPERATURE + aquaspy::DEPTHS.len()).collect();
+        let mut readings = match data::fetch_sheet(data::AQUASPY_FILE, &data::sheet_name(team)).await {
+            Ok(range) => data::table(&range, aquaspy::TIMESTAMP, &columns),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        readings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let days = soil_temperature::daily(&readings);
+        let Some((today, latest)) = days.last() else {
+            return vec![Message::ShowNotes(format!("Team #{team} soil temperature"), vec!["No temperature readings.".to_string()])];
+        };
+
+        let profile = |pick: fn(&soil_temperature::DayRange) -> f64| aquaspy::DEPTHS.iter().zip(latest)
+            .filter_map(|(depth, range)| Some((pick(range.as_ref()?), *depth)))
+            .collect::<Vec<(f64, f64)>>();
+        let season: Vec<(f64, f64)> = aquaspy::DEPTHS.iter().enumerate()
+            .filter_map(|(sensor, depth)| {
+                let means: Vec<f64> = days.iter().filter_map(|(_, ranges)| ranges[sensor].map(|range| range.mean)).collect();
+                (!means.is_empty()).then(|| (means.iter().sum::<f64>() / means.len() as f64, *depth))
+            })
+            .collect();
+
+        let mut notes = vec![match soil_temperature::planting_ready(&days) {
+            Some(day) => format!("4 in soil held {} F or warmer from {} ({} days running), warm enough to plant.", soil_temperature::PLANTING, dates::label(day), soil_temperature::PLANTING_DAYS),
+            None => format!("4 in soil never held {} F for {} days running.", soil_temperature::PLANTING, soil_temperature::PLANTING_DAYS),
+        }];
+        let hot = soil_temperature::heat_stress(&days);
+        if hot.is_empty() {
+            notes.push(format!("The top foot never reached {} F.", soil_temperature::HEAT_STRESS));
+        } else {
+            notes.push(format!("{} days the top foot reached {} F or more:", hot.len(), soil_temperature::HEAT_STRESS));
+            notes.extend(hot.iter().map(|(day, hottest)| format!("{}: {:.0} F", dates::label(*day), hottest)));
+        }
+        // Heat flow on the latest day, then how often the top layer was taking heat in
+        let flux = soil_temperature::flux(latest);
+        notes.push(format!("Heat flow on {} between neighbouring sensors: {}.", dates::label(*today),
+            flux.iter().enumerate().map(|(index, flux)| format!("{}-{} in {}", aquaspy::DEPTHS[index], aquaspy::DEPTHS[index + 1], flux.name())).collect::<Vec<_>>().join(", ")));
+        let warming = days.iter().filter(|(_, ranges)| soil_temperature::flux(ranges).first() == Some(&soil_temperature::Flux::Down)).count();
+        notes.push(format!("Heat flowed down from 4 to 8 in on {} of {} days.", warming, days.len()));
+
+        let series = vec![
+            Series::new(format!("{} min", dates::label(*today)), BLUE, profile(|range| range.min)),
+            Series::new(format!("{} mean", dates::label(*today)), BLACK, profile(|range| range.mean)),
+            Series::new(format!("{} max", dates::label(*today)), RED, profile(|range| range.max)),
+            Series::new("Season mean", RGBColor(120, 120, 120), season),
+        ];
+        vec![
+            Message::MakeProfile(format!("Team #{team} soil temperature profile"), "Temperature (F)".to_string(), series),
+            Message::ShowNotes(format!("Team #{team} soil temperature"), notes),
+        ]
+    }
+
+    // Daily VPD and vapour pressures, with the runs of high demand days to irrigate ahead of
+    async fn fetch_vpd(team: u32) -> Vec<Message> {
+        let air = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(team)).await {
+            Ok(range) => vpd::air(&data::table(&range, arable::TIMESTAMP, &vpd::COLUMNS)),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        if air.is_empty() {
+            return vec![Message::ShowNotes(format!("Team #{team} VPD"), vec!["No days with temperature and humidity readings.".to_string()])];
+        }
+
+        let runs = vpd::high_demand_runs(&air);
+        let high = air.iter().filter(|day| day.high_demand()).count();
+        let mut notes = vec![
+            format!("Season mean VPD {:.2} kPa, afternoon VPD up to {:.2} kPa.",
+                air.iter().map(|day| day.deficit).sum::<f64>() / air.len() as f64, air.iter().map(|day| day.afternoon).fold(0.0, f64::max)),
+            format!("Dew point from {:.0} F to {:.0} F.",
+                air.iter().map(|day| day.dew_point).fold(f64::MAX, f64::min), air.iter().map(|day| day.dew_point).fold(f64::MIN, f64::max)),
+            format!("{} high demand days (daily VPD {} kPa or more) in {} runs:", high, vpd::HIGH_DEMAND, runs.len()),
+        ];
+        // The profile wants to be full going into a run, so the day before is the one to water by
+        for (first, last) in &runs {
+            let peak = air.iter().filter(|day| *first <= day.day && day.day <= *last).map(|day| day.deficit).fold(0.0, f64::max);
+            notes.push(format!("{} to {}: up to {:.2} kPa, irrigate by {}.", dates::label(*first), dates::label(*last), peak, dates::label(first - 1.0)));
+        }
+
+        let series = vec![
+            Series::new("VPD", RED, air.iter().map(|day| (day.day, day.deficit)).collect()),
+            Series::new("Afternoon VPD", RGBColor(230, 120, 0), air.iter().map(|day| (day.day, day.afternoon)).collect()),
+            Series::new("Saturation vapour pressure", BLUE, air.iter().map(|day| (day.day, day.saturation)).collect()),
+            Series::new("Actual vapour pressure", GREEN, air.iter().map(|day| (day.day, day.actual)).collect()),
+            Series::new(format!("High demand ({} kPa)", vpd::HIGH_DEMAND), BLACK, vec![(air[0].day, vpd::HIGH_DEMAND), (air[air.len() - 1].day, vpd::HIGH_DEMAND)]),
+        ];
+        let markers = runs.iter().map(|(first, _)| Marker::new(*first, "High VPD", RED)).collect();
+        vec![
+            Message::MakeChart(format!("Team #{team} vapour pressure (kPa) / time"), series, markers),
+            Message::ShowNotes(format!("Team #{team} VPD"), notes),
+        ]
+    }
+
+    // Heat, water and VPD stress days for every team, split up by crop stage
+    async fn fetch_stress_days(settings: BalanceSettings, pivot: Pivot, stages: Vec<StageSpan>, log: Vec<Entry>) -> Vec<Message> {
+        // Both workbooks once for every team
+        let sheets = match data::fetch_sheets(data::ARABLE_FILE).await {
+            Ok(sheets) => sheets,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let probes = data::fetch_sheets(data::AQUASPY_FILE).await.unwrap_or_else(|e| {
+            console::error!(e);
+            HashMap::new()
+        });
+
+        let heat = settings.crop.heat_stress();
+        let profile = soil::profile(&settings.plot_id);
+        let mut rows = Vec::new();
+        let mut totals = Vec::new();
+        for &team in data::ARABLE_TEAMS.iter() {
+            let sheet = data::sheet_name(team);
+            let Some(range) = sheets.get(&sheet) else {
+                console::error!(format!("No sheet {sheet} in the Arable workbook"));
+                continue;
+            };
+            let weather = data::table(range, arable::TIMESTAMP, &vpd::COLUMNS);
+            let (inputs, observed, initial_depletion) = App::balance_days(&settings, pivot, &irrigation_log::applied(&log, team), range, probes.get(&sheet));
+            let balance = water_balance::simulate(&settings, &profile, &inputs, initial_depletion);
+            let air = vpd::air(&weather);
+
+            // The probe's depletion beats the model's on days it has one
+            let days: Vec<stress::StressDay> = balance.iter()
+                .map(|day| {
+                    let depletion = observed.iter().find(|(other, _)| *other == day.day).map(|(_, depletion)| *depletion).unwrap_or(day.depletion);
+                    stress::StressDay {
+                        day: day.day,
+                        heat: weather.iter().any(|(other, values)| other.floor() == day.day && values[0].map(|max| max >= heat).unwrap_or(false)),
+                        water: depletion > day.raw,
+                        vpd: air.iter().any(|air| air.day == day.day && air.high_demand()),
+                    }
+                })
+                .collect();
+
+            let counts = stress::by_stage(&days, &stages);
+            for count in &counts {
+                rows.push(vec![format!("#{team}"), count.stage.clone(), count.days.to_string(), count.heat.to_string(), count.water.to_string(), count.vpd.to_string()]);
+            }
+            if let Some(season) = counts.last() {
+                totals.push((team, season.clone()));
+            }
+        }
+        if totals.is_empty() {
+            return Vec::new();
+        }
+
+        let groups = totals.iter().map(|(team, _)| format!("#{team}")).collect();
+        let bar = |label: &str, color: RGBColor, pick: fn(&stress::Counts) -> usize| {
+            Series::new(label, color, totals.iter().enumerate().map(|(index, (_, counts))| (index as f64, pick(counts) as f64)).collect())
+        };
+        let bars = vec![
+            bar(&format!("Heat (max {heat} F+)"), RED, |counts| counts.heat),
+            bar("Water (past MAD)", BLUE, |counts| counts.water),
+            bar(&format!("VPD ({} kPa+)", vpd::HIGH_DEMAND), RGBColor(230, 120, 0), |counts| counts.vpd),
+        ];
+        let header = ["Team", "Stage", "Days", "Heat", "Water", "VPD"].map(String::from).to_vec();
+        vec![
+            Message::MakeBars(format!("{} stress days by team", settings.crop.name()), groups, bars, Vec::new()),
+            Message::ShowTable(format!("{} stress days by stage", settings.crop.name()), header, rows),
+        ]
+    }
+
+    // Field ET split into soil evaporation and transpiration, stacked so the top is field ET
+    async fn fetch_et_partition(team: u32, cumulative: bool) -> Vec<Message> {
+        let days = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(team)).await {
+            Ok(range) => partition::daily(&data::table(&range, arable::TIMESTAMP, &partition::COLUMNS)),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        if days.is_empty() {
+            return vec![Message::ShowNotes(format!("Team #{team} evaporation / transpiration"), vec!["No days with both field and canopy ET.".to_string()])];
+        }
+
+        let season = partition::total(&days);
+        let mut notes = vec![
+            format!("Season: {:.1} in evaporation, {:.1} in transpiration, {:.0}% of field ET came off the soil.",
+                season.evaporation, season.transpiration, season.evaporation_share() * 100.0),
+            "A high evaporation share with a full canopy points at wetting the surface too often, residue cuts it early on.".to_string(),
+        ];
+        for week in days.chunks(7) {
+            let total = partition::total(week);
+            notes.push(format!("Week of {}: E {:.2} in, T {:.2} in ({:.0}% E).", dates::label(week[0].day), total.evaporation, total.transpiration, total.evaporation_share() * 100.0));
+        }
+
+        let shown = if cumulative { partition::cumulative(&days) } else { days };
+        let layers = vec![
+            Series::new("Transpiration (canopy ET)", GREEN, shown.iter().map(|day| (day.day, day.transpiration)).collect()),
+            Series::new("Soil evaporation (field - canopy ET)", RGBColor(160, 110, 50), shown.iter().map(|day| (day.day, day.evaporation)).collect()),
+        ];
+        // Wet surfaces are what drives evaporation, so the probe's events help read it
+        let markers = match App::wetting_events(team).await {
+            Ok((_, events)) => App::event_markers(&events),
+            Err(_) => Vec::new(),
+        };
+        let caption = if cumulative { "cumulative ET (in)" } else { "ET (in)" };
+        vec![
+            Message::MakeStacked(format!("Team #{team} {caption} / time"), layers, markers),
+            Message::ShowNotes(format!("Team #{team} evaporation / transpiration"), notes),
+        ]
+    }
+
+    // Irrigation (day, inches) the probe saw a team put on
+    async fn probe_irrigation(team: u32) -> Vec<(f64, f64)> {
+        match App::wetting_events(team).await {
+            Ok((_, events)) => App::irrigation_events(&events),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    // Irrigation (day, inches) out of the probe's wetting events
+    fn irrigation_events(events: &[events::WettingEvent]) -> Vec<(f64, f64)> {
+        events.iter()
+            .filter(|event| event.kind == events::EventKind::Irrigation)
+            .map(|event| (event.start.floor(), event.applied))
+            .collect()
+    }
+
+    // Irrigation (day, inches) that reached the root zone out of what a team logged pumping
+    fn net_irrigation(logged: &[(f64, f64)], efficiency: f64) -> Vec<(f64, f64)> {
+        logged.iter().map(|(day, amount)| (*day, amount * efficiency)).collect()
+    }
+
+    // Irrigation (day, inches) that reached a team's root zone, what they logged at the pivot's
+    // efficiency or what the probe saw when nothing is logged, and whether it came from the log
+    //
+    // The log has what was pumped, the probe only ever sees what got into the soil.
+    async fn applied_irrigation(team: u32, logged: &[(f64, f64)], efficiency: f64) -> (Vec<(f64, f64)>, bool) {
+        if logged.is_empty() {
+            (App::probe_irrigation(team).await, false)
+        } else {
+            (App::net_irrigation(logged, efficiency), true)
+        }
+    }
+
+    // Inches of irrigation on one day
+    fn irrigation_on(irrigation: &[(f64, f64)], day: f64) -> f64 {
+        irrigation.iter().filter(|(other, _)| *other == day).fold(0.0, |total, (_, amount)| total + amount)
+    }
+
+    // Rain, rain + irrigation and ET added up from planting, the gap between what came in and
+    // what was used is the soil's storage change
+    async fn fetch_water_budget(team: u32, planting: f64, pivot: Pivot, stages: Vec<StageSpan>, logged: Vec<(f64, f64)>) -> Vec<Message> {
+        let rows = match data::fetch_sheet(data::ARABLE_FILE, &data::sheet_name(team)).await {
+            Ok(range) => data::table(&range, arable::TIMESTAMP, &[arable::PRECIPITATION, arable::FIELD_ET]),
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        // What the team logged is what went on, the probe is the fallback
+        let (irrigation, from_log) = App::applied_irrigation(team, &logged, pivot.efficiency).await;
+        let days: Vec<budget::WaterDay> = rows.iter()
+            .filter(|(day, _)| day.floor() >= planting)
+            .map(|(day, values)| budget::WaterDay {
+                day: day.floor(),
+                rain: values[0].unwrap_or(0.0),
+                irrigation: App::irrigation_on(&irrigation, day.floor()),
+                et: values[1].unwrap_or(0.0),
+            })
+            .collect();
+        if days.is_empty() {
+            return vec![Message::ShowNotes(format!("Team #{team} water budget"), vec![format!("No Arable readings since planting on {}.", dates::label(planting))])];
+        }
+
+        let season = budget::total(&days);
+        let mut notes = vec![
+            format!("Since planting on {}: {:.1} in rain + {:.1} in irrigation against {:.1} in ET, the soil {} {:.1} in (less runoff and drainage).",
+                dates::label(planting), season.rain, season.irrigation, season.et,
+                if season.storage_change() >= 0.0 { "gained" } else { "gave up" }, season.storage_change().abs()),
+        ];
+        notes.push(match (from_log, irrigation.is_empty()) {
+            (true, _) => format!("Irrigation from the team's log, the {:.0}% of it the pivot gets into the soil.", pivot.efficiency * 100.0),
+            (false, false) => "Irrigation as the probe saw it, nothing logged for this team.".to_string(),
+            (false, true) => "No irrigation logged or seen by a probe for this team.".to_string(),
+        });
+        // The probe measures what the soil really held on to
+        if let Ok((moisture, _)) = App::wetting_events(team).await {
+            let stored: Vec<(f64, f64)> = moisture.iter()
+                .filter(|(time, _)| *time >= planting)
+                .filter_map(|(time, readings)| Some((*time, events::storage(readings)?)))
+                .collect();
+            if let (Some(first), Some(last)) = (stored.first(), stored.last()) {
+                notes.push(format!("The probe's {} in profile changed by {:+.1} in from {} to {}, the rest ran off, drained or came from deeper.",
+                    aquaspy::DEPTHS[aquaspy::DEPTHS.len() - 1], last.1 - first.1, dates::label(first.0), dates::label(last.0)));
+            }
+        }
+
+        let row = |name: String, total: &budget::WaterDay| vec![name, format!("{:.2}", total.rain), format!("{:.2}", total.irrigation), format!("{:.2}", total.et), format!("{:+.2}", total.storage_change())];
+        let mut table: Vec<Vec<String>> = budget::by_stage(&days, &stages).iter().map(|(name, total)| row(name.clone(), total)).collect();
+        table.push(row("Season".to_string(), &season));
+        let header = ["Stage", "Rain", "Irrigation", "ET", "Storage"].map(String::from).to_vec();
+
+        let cumulative = budget::cumulative(&days);
+        let series = vec![
+            Series::new("Rain", BLUE, cumulative.iter().map(|day| (day.day, day.rain)).collect()),
+            Series::new("Rain + irrigation", GREEN, cumulative.iter().map(|day| (day.day, day.rain + day.irrigation)).collect()),
+            Series::new("Crop ET", RED, cumulative.iter().map(|day| (day.day, day.et)).collect()),
+        ];
+        let gap = Gap {
+            upper: 1,
+            lower: 2,
+            above: ("Soil storage gained".to_string(), GREEN),
+            below: ("Soil storage drawn down".to_string(), RED),
+        };
+        // Logged irrigation gets marked on every chart anyway
+        let markers = match from_log {
+            true => Vec::new(),
+            false => irrigation.iter().map(|(day, amount)| Marker::new(*day, format!("Irrigation {amount:.2} in"), RED)).collect(),
+        };
+        vec![
+            Message::MakeGapChart(format!("Team #{team} cumulative water budget (in) / time"), series, gap, markers),
+            Message::ShowNotes(format!("Team #{team} water budget"), notes),
+            Message::ShowTable(format!("Team #{team} budget by stage (in)"), header, table),
+        ]
+    }
+
+    // Decision workbook for a team from its log and next week's plan, checked against the allocation
+    async fn build_submission(team: u32, settings: BalanceSettings, pivot: Pivot, entries: Vec<Entry>, allocation: f64) -> Vec<Message> {
+        let logged = irrigation_log::applied(&entries, team);
+        let entries: Vec<&Entry> = entries.iter().collect();
+        let mut notes = Vec::new();
+        let plan = match App::plan_next_week(team, &settings, pivot, &logged).await {
+            Ok((_, _, recommendation)) => recommendation.plan,
+            Err(e) => {
+                console::log!(format!("No plan for team #{team}: {e}"));
+                notes.push(if data::ARABLE_TEAMS.contains(&team) {
+                    "No water balance for this team, the workbook only has the logged decisions.".to_string()
+                } else {
+                    format!("No Arable data for team #{team}, so no plan for next week, the workbook only has the logged decisions.")
+                });
+                Vec::new()
+            }
+        };
+
+        let problems = submission::check(&entries, &plan, allocation, &pivot);
+        let logged = entries.iter().fold(0.0, |total, entry| total + entry.amount);
+        let planned = plan.iter().fold(0.0, |total, day| total + day.irrigation);
+        notes.insert(0, format!("{} decisions logged ({:.2} in), {:.2} in planned for next week, {:.2} in of the {:.2} in allocation left after it.",
+            entries.len(), logged, planned, allocation - logged - planned, allocation));
+        for (week, total) in submission::weekly(&entries) {
+            notes.push(format!("Week of {}: {:.2} in.", dates::label(week), total));
+        }
+        if problems.is_empty() {
+            notes.push("Totals check out against the allocation and the pivot.".to_string());
+        }
+        notes.extend(problems);
+        notes.push(format!("{}, check it against the form you actually hand in.", submission::NOT_OFFICIAL));
+
+        match submission::workbook(team, &entries, &plan, allocation, &pivot) {
+            Ok(bytes) => vec![
+                Message::SubmissionReady(team, bytes),
+                Message::ShowNotes(format!("Team #{team} submission"), notes),
+            ],
+            Err(e) => {
+                console::error!(e.to_string());
+                vec![Message::ShowNotes(format!("Team #{team} submission"), vec![format!("Couldn't make the workbook: {e}")])]
+            }
+        }
+    }
+
+    // Every team's logged irrigation in acre-inches against the allocation, and what pumping it cost
+    fn allocation_tracker(log: &[Entry], settings: &AllocationSettings) -> Vec<Message> {
+        let usage: Vec<(u32, allocation::Usage)> = data::teams().into_iter()
+            .map(|team| (team, allocation::usage(settings, irrigation_log::applied(log, team).iter().fold(0.0, |total, (_, amount)| total + amount))))
+            .collect();
+        let allowed = settings.acre_inches(settings.allocation);
+
+        let mut notes = vec![
+            format!("{:.1} in on {:.0} acres is {:.0} acre-in ({:.1} acre-ft) for the season.", settings.allocation, settings.area, allowed, allocation::acre_feet(allowed)),
+            format!("{:.0} ft lift + {:.0} psi is {:.0} ft of head, {:.1} kWh an acre-inch at {:.0}% efficiency, ${:.2} an acre-inch at ${:.2}/kWh.",
+                settings.lift, settings.pressure, settings.head(), settings.kwh_per_acre_inch(), settings.efficiency * 100.0, settings.kwh_per_acre_inch() * settings.price, settings.price),
+        ];
+        for (team, usage) in usage.iter().filter(|(_, usage)| usage.remaining < 0.0) {
+            notes.push(format!("Team #{team} is {:.0} acre-in over its allocation.", -usage.remaining));
+        }
+        if usage.iter().all(|(_, usage)| usage.applied == 0.0) {
+            notes.push("Nothing logged yet, add irrigation in the irrigation log.".to_string());
+        }
+
+        let rows = usage.iter()
+            .map(|(team, usage)| vec![
+                format!("#{team}"),
+                format!("{:.2}", usage.applied),
+                format!("{:.0}", usage.acre_inches),
+                format!("{:.1}", allocation::acre_feet(usage.acre_inches)),
+                format!("{:.1}", allocation::acre_feet(usage.remaining)),
+                format!("{:.0}", usage.kwh),
+                format!("{:.0}", usage.cost),
+            ])
+            .collect();
+        let header = ["Team", "Applied (in)", "Acre-in", "Acre-ft", "Left (acre-ft)", "kWh", "Cost ($)"].map(String::from).to_vec();
+
+        let groups = usage.iter().map(|(team, _)| format!("#{team}")).collect();
+        let bars = vec![
+            Series::new("Used (acre-in)", BLUE, usage.iter().enumerate().map(|(index, (_, usage))| (index as f64, usage.acre_inches)).collect()),
+            Series::new("Left (acre-in)", GREEN, usage.iter().enumerate().map(|(index, (_, usage))| (index as f64, usage.remaining.max(0.0))).collect()),
+        ];
+        vec![
+            Message::MakeBars(format!("Allocation used and left ({allowed:.0} acre-in each)"), groups, bars, Vec::new()),
+            Message::ShowNotes("Allocation and pumping cost".to_string(), notes),
+            Message::ShowTable("Allocation by team".to_string(), header, rows),
+        ]
+    }
+
+    // Every scenario replayed through a team's season, depletion side by side and a table of
+    // how each one did
+    #[allow(clippy::too_many_arguments)]
+    async fn fetch_scenarios(team: u32, settings: BalanceSettings, pivot: Pivot, rain: SimulationSettings, pumping: AllocationSettings,
+                             what_if: WhatIfSettings, scenarios: Vec<Scenario>, logged: Vec<(f64, f64)>) -> Vec<Message> {
+        let (inputs, _, initial_depletion) = match App::balance_inputs(team, &settings, pivot, &logged).await {
+            Ok(result) => result,
+            Err(e) => {
+                console::error!(e);
+                return Vec::new();
+            }
+        };
+        let Some(last) = inputs.last().map(|input| input.day) else {
+            return Vec::new();
+        };
+        if scenarios.is_empty() {
+            return vec![Message::ShowNotes(format!("Team #{team} scenarios"), vec!["Add a scenario to compare.".to_string()])];
+        }
+
+        // One season of rain as recorded, or as many as the rainfall simulator draws
+        let seasons = match what_if.rain {
+            RainSource::Recorded => vec![inputs],
+            RainSource::Simulated => {
+                let models: Vec<rain_generator::WeekModel> = match data::fetch_sheet(rainfall::FILE, rainfall::SHEET).await {
+                    Ok(range) => rainfall::weeks(&range, dates::to_ymd(last).0).iter().map(rain_generator::fit).collect(),
+                    Err(e) => {
+                        console::error!(e);
+                        return Vec::new();
+                    }
+                };
+                scenario::rain_draws(&inputs, what_if.from, &projection::extend(&models, what_if.from, last), rain.seed, rain.seasons.max(1))
+            },
+        };
+
+        let profile = soil::profile(&settings.plot_id);
+        let cost = pumping.kwh_per_acre_inch() * pumping.price;
+        let colors = [BLUE, RED, GREEN, RGBColor(230, 120, 0), MAGENTA, CYAN, BLACK];
+        let mut series = Vec::new();
+        let mut rows = Vec::new();
+        for (index, scenario) in scenarios.iter().enumerate() {
+            let outcomes: Vec<scenario::Outcome> = seasons.iter()
+                .map(|inputs| scenario::run(&settings, &profile, inputs, initial_depletion, scenario, what_if.from, pivot.efficiency))
+                .collect();
+            let mean = |pick: fn(&scenario::Outcome) -> f64| outcomes.iter().map(pick).sum::<f64>() / outcomes.len() as f64;
+
+            // The middle season on every day when there are several
+            let depletion: Vec<Vec<(f64, f64)>> = outcomes.iter().map(|outcome| outcome.depletion.clone()).collect();
+            series.push(Series::new(scenario.name.clone(), colors[index % colors.len()], projection::fan(&depletion, 50.0)));
+            if index == 0 {
+                series.push(Series::new("Readily available water", RGBColor(120, 120, 120), outcomes[0].raw.clone()));
+            }
+
+            rows.push(vec![
+                scenario.name.clone(),
+                format!("{:.2}", mean(|outcome| outcome.irrigation)),
+                format!("{:.1}", mean(|outcome| outcome.stress_days as f64)),
+                format!("{:.2}", mean(|outcome| outcome.drainage)),
+                format!("{:.2}", mean(|outcome| outcome.et_lost)),
+                format!("{:.0}", mean(|outcome| outcome.irrigation) * cost),
+                format!("{:.2}", mean(|outcome| outcome.depletion.last().map(|(_, depletion)| *depletion).unwrap_or(0.0))),
+            ]);
+        }
+
+        let notes = vec![
+            format!("Irrigation as the water balance has it (logged, or seen by the probe) up to {}, each scenario's own schedule from then to {}.", dates::label(what_if.from), dates::label(last)),
+            match what_if.rain {
+                RainSource::Recorded => "Rain as the Arable recorded it.".to_string(),
+                RainSource::Simulated => format!("Rain from {} simulated seasons (seed {}), the table averages them and the chart is the median day by day.", seasons.len(), rain.seed),
+            },
+            format!("Irrigation amounts are gross, {:.0}% reaches the root zone. Pumping at ${:.2} an acre-inch from the allocation settings.", pivot.efficiency.max(0.01) * 100.0, cost),
+            "Stress days are days the root zone ends drier than readily available water, drainage is what went past the roots.".to_string(),
+        ];
+        let header = ["Scenario", "Irrigation (in)", "Stress days", "Drainage (in)", "ET lost (in)", "Cost ($/ac)", "End depletion (in)"].map(String::from).to_vec();
+        vec![
+            Message::MakeChart(format!("Team #{team} scenario depletion (in) / time"), series, Vec::new()),
+            Message::ShowNotes(format!("Team #{team} scenarios"), notes),
+            Message::ShowTable(format!("Team #{team} scenarios side by side"), header, rows),
+        ]
+    }
 }
 
 // Entry point of the application
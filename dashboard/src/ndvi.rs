@@ -0,0 +1,66 @@
+// Crop coefficient from the Arable NDVI instead of the FAO-56 calendar curve
+//
+// Canopy cover from Trout, Johnson & Gartung (2008), the coefficient from cover with the
+// density coefficient of Allen & Pereira (2009).
+
+use crate::crop::CropCoefficients;
+
+// Days the smoothing averages over, centred on the day
+pub const WINDOW: f64 = 7.0;
+
+// Moving average over `window` days, one point out per point in (gaps are just skipped)
+pub fn smooth(points: &[(f64, f64)], window: f64) -> Vec<(f64, f64)> {
+    points.iter()
+        .map(|&(day, _)| {
+            let near: Vec<f64> = points.iter()
+                .filter(|(other, _)| (other - day).abs() <= window / 2.0)
+                .map(|(_, value)| *value)
+                .collect();
+            (day, near.iter().sum::<f64>() / near.len() as f64)
+        })
+        .collect()
+}
+
+// Fraction of the ground the canopy covers
+pub fn canopy_cover(ndvi: f64) -> f64 {
+    (1.18 * ndvi - 0.06).clamp(0.0, 1.0)
+}
+
+// Crop coefficient for a canopy cover, between the crop's bare soil and full cover values
+pub fn kc(coefficients: &CropCoefficients, cover: f64) -> f64 {
+    // Canopy height grows roughly with cover
+    let height = coefficients.height * cover;
+    let density = (2.0 * cover).min(cover.powf(1.0 / (1.0 + height))).min(1.0);
+    coefficients.kc_ini + (coefficients.kc_mid - coefficients.kc_ini) * density
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crop::Crop;
+
+    #[test]
+    fn kc_stays_between_bare_soil_and_full_cover() {
+        let corn = Crop::Corn.coefficients();
+        assert_eq!(canopy_cover(0.0), 0.0);
+        assert_eq!(canopy_cover(0.95), 1.0);
+        assert_eq!(kc(&corn, canopy_cover(0.05)), corn.kc_ini);
+        assert_eq!(kc(&corn, canopy_cover(0.95)), corn.kc_mid);
+        let mut last = corn.kc_ini;
+        for step in 0..=20 {
+            let value = kc(&corn, canopy_cover(step as f64 / 20.0));
+            assert!((corn.kc_ini..=corn.kc_mid).contains(&value) && value >= last, "{value}");
+            last = value;
+        }
+    }
+
+    #[test]
+    fn smoothing_skips_gaps() {
+        let points = [(1.0, 0.2), (2.0, 0.4), (3.0, 0.6), (10.0, 0.8)];
+        let smoothed = smooth(&points, 3.0);
+        assert_eq!(smoothed.len(), points.len());
+        assert!((smoothed[1].1 - 0.4).abs() < 1e-9);
+        assert!((smoothed[0].1 - 0.3).abs() < 1e-9);
+        assert_eq!(smoothed[3], (10.0, 0.8));
+    }
+}
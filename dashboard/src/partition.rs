@@ -0,0 +1,77 @@
+// Field ET split into soil evaporation and transpiration
+//
+// Arable's canopy ET is taken as what the plants transpire, and whatever field ET has on top
+// of it as evaporation off the soil surface. Inches a day like the Arable columns.
+
+use crate::data::arable;
+
+// Arable columns `daily` expects, in order
+pub const COLUMNS: [usize; 2] = [arable::FIELD_ET, arable::CANOPY_ET];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Partition {
+    pub day: f64, // excel serial
+    pub evaporation: f64,
+    pub transpiration: f64,
+}
+
+impl Partition {
+    // Share of the day's ET that came off the soil (0 - 1)
+    pub fn evaporation_share(&self) -> f64 {
+        let total = self.evaporation + self.transpiration;
+        if total > 0.0 { self.evaporation / total } else { 0.0 }
+    }
+}
+
+// Days with both readings, from rows read with COLUMNS
+pub fn daily(rows: &[(f64, Vec<Option<f64>>)]) -> Vec<Partition> {
+    rows.iter()
+        .filter_map(|(day, values)| {
+            let field = values[0]?.max(0.0);
+            // Canopy ET now and then comes out a touch above field ET, it can't be more than all of it
+            let transpiration = values[1]?.clamp(0.0, field);
+            Some(Partition { day: day.floor(), evaporation: field - transpiration, transpiration })
+        })
+        .collect()
+}
+
+// Running totals from the first day
+pub fn cumulative(days: &[Partition]) -> Vec<Partition> {
+    days.iter()
+        .scan((0.0, 0.0), |(evaporation, transpiration), day| {
+            *evaporation += day.evaporation;
+            *transpiration += day.transpiration;
+            Some(Partition { day: day.day, evaporation: *evaporation, transpiration: *transpiration })
+        })
+        .collect()
+}
+
+// Everything in a run of days added up
+pub fn total(days: &[Partition]) -> Partition {
+    cumulative(days).last().copied().unwrap_or(Partition { day: 0.0, evaporation: 0.0, transpiration: 0.0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canopy_et_is_transpiration() {
+        let rows = vec![
+            (45474.5, vec![Some(0.25), Some(0.15)]),
+            (45475.5, vec![Some(0.20), Some(0.22)]), // canopy a touch over field ET
+            (45476.5, vec![Some(0.30), None]),
+        ];
+        let days = daily(&rows);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].day, 45474.0);
+        assert!((days[0].evaporation - 0.10).abs() < 1e-9);
+        assert_eq!(days[1], Partition { day: 45475.0, evaporation: 0.0, transpiration: 0.20 });
+
+        let season = total(&days);
+        assert!((season.transpiration - 0.35).abs() < 1e-9);
+        assert!((season.evaporation_share() - 0.10 / 0.45).abs() < 1e-9);
+        assert_eq!(cumulative(&days).last().copied(), Some(season));
+        assert_eq!(total(&[]).evaporation_share(), 0.0);
+    }
+}
@@ -0,0 +1,115 @@
+// How deep each wetting event's water got, and how much went past the roots
+//
+// The front has reached a sensor once it's gone up by FRONT_RISE since the event started.
+// Whatever the sensors at DEEP and below gain is counted as lost, corn and sorghum get very
+// little out of the bottom of the probe. Water that goes past 48" doesn't show up at all, so
+// when the bottom sensor gets wet the loss is only a lower bound.
+
+use crate::data::aquaspy;
+use crate::dates;
+use crate::events::{self, WettingEvent};
+
+// Rise (volumetric % points) that means the water got to a sensor
+const FRONT_RISE: f64 = 1.0;
+// Longest (days) an event is followed for, unless the next one starts sooner
+const FOLLOW: f64 = 4.0;
+// Top of what's counted as below the root zone (inches)
+pub const DEEP: f64 = 36.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Front {
+    pub event: WettingEvent,
+    pub depth: Option<f64>, // deepest sensor the water reached (inches)
+    pub hours: Option<f64>, // from the start of the event to getting there
+    pub lost: f64,          // inches gained at DEEP and below
+    pub below_probe: bool,  // the bottom sensor got wet, some water went deeper than we can see
+}
+
+// Follow every event down the probe (readings in time order)
+pub fn fronts(moisture: &[(f64, Vec<Option<f64>>)], events: &[WettingEvent]) -> Vec<Front> {
+    events.iter().enumerate()
+        .map(|(index, event)| {
+            let until = events.get(index + 1).map(|next| next.start).unwrap_or(f64::MAX).min(event.start + FOLLOW);
+            let window: Vec<&(f64, Vec<Option<f64>>)> = moisture.iter().filter(|(time, _)| event.start <= *time && *time < until).collect();
+            let Some((_, first)) = window.first() else {
+                return Front { event: *event, depth: None, hours: None, lost: 0.0, below_probe: false };
+            };
+
+            let mut depth = None;
+            let mut hours = None;
+            let mut lost = 0.0;
+            for (sensor, &sensor_depth) in aquaspy::DEPTHS.iter().enumerate() {
+                let Some(before) = first[sensor] else { continue };
+                let wetted = window.iter().find(|(_, readings)| readings[sensor].map(|reading| reading - before >= FRONT_RISE).unwrap_or(false));
+                if let Some((time, _)) = wetted {
+                    depth = Some(sensor_depth);
+                    hours = Some((time - event.start) * 24.0);
+                }
+                // A sensor stands for the 4 inches above it
+                if sensor_depth > DEEP {
+                    let most = window.iter().filter_map(|(_, readings)| readings[sensor]).fold(before, f64::max);
+                    lost += (most - before) / 100.0 * events::thickness();
+                }
+            }
+            let bottom = aquaspy::DEPTHS[aquaspy::DEPTHS.len() - 1];
+            Front { event: *event, depth, hours, lost, below_probe: depth == Some(bottom) }
+        })
+        .collect()
+}
+
+pub fn describe(front: &Front) -> String {
+    let reach = match (front.depth, front.hours) {
+        (Some(depth), Some(hours)) => format!("reached {depth} in after {hours:.0} h"),
+        _ => "didn't get past the shallow sensors".to_string(),
+    };
+    let lost = if front.below_probe {
+        format!("at least {:.2} in lost below {DEEP} in (went past the probe)", front.lost)
+    } else {
+        format!("{:.2} in lost below {DEEP} in", front.lost)
+    };
+    format!("{} {}: {}, {}.", dates::label(front.event.start), front.event.label(), reach, lost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventKind;
+
+    // Two hourly readings for two days after an event, every sensor down to `reach` inches
+    // gains 2 points once the water gets to it (an hour for every 4 inches)
+    fn front_to(reach: f64) -> Front {
+        let start = 45474.0;
+        let moisture: Vec<(f64, Vec<Option<f64>>)> = (0..24)
+            .map(|step| {
+                let hours = step as f64 * 2.0;
+                let readings = aquaspy::DEPTHS.iter()
+                    .map(|&depth| Some(if depth <= reach && hours >= depth / 4.0 { 22.0 } else { 20.0 }))
+                    .collect();
+                (start + hours / 24.0, readings)
+            })
+            .collect();
+        let event = WettingEvent { start, peak: start + 0.1, end: start + 0.5, rise: 2.0, applied: 1.0, rain: 0.0, kind: EventKind::Irrigation };
+        fronts(&moisture, &[event]).remove(0)
+    }
+
+    #[test]
+    fn only_water_below_36_in_is_lost() {
+        let front = front_to(40.0);
+        assert_eq!(front.depth, Some(40.0));
+        assert!((front.hours.unwrap() - 10.0).abs() < 1e-6);
+        assert!((front.lost - 0.08).abs() < 1e-9);
+        assert!(!front.below_probe);
+        // The 36 in sensor is still in the root zone
+        let front = front_to(36.0);
+        assert_eq!(front.depth, Some(36.0));
+        assert_eq!(front.lost, 0.0);
+    }
+
+    #[test]
+    fn bottom_sensor_means_it_went_past_the_probe() {
+        let front = front_to(48.0);
+        assert!(front.below_probe);
+        assert!((front.lost - 3.0 * 0.08).abs() < 1e-9);
+        assert!(describe(&front).contains("at least"));
+    }
+}
@@ -0,0 +1,150 @@
+// How much irrigation is still needed to the end of the season, as a spread over simulated
+// rainfall seasons
+//
+// Every season runs the bucket from today's depletion and tops it back up to the readily
+// available water whenever it would go past it, so the crop never gets stressed. What that
+// takes is the water still needed.
+
+use crate::dates;
+use crate::rain_generator::{self, WeekModel};
+use crate::soil::SoilLayer;
+use crate::water_balance::{self, BalanceSettings, DayInput};
+
+// Where the projection starts from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Start {
+    pub today: f64,     // excel serial, the projection starts the day after
+    pub depletion: f64, // inches, end of today
+    pub et: f64,        // ET the crop coefficient is applied to, kept at this for the rest of the season
+}
+
+// Last day the projection runs to (end of September)
+pub fn season_end(today: f64) -> f64 {
+    dates::from_ymd(dates::to_ymd(today).0, 9, 30)
+}
+
+// Weekly models covering every day from `from` up to `end`, the file stops early September so
+// its last week stands in for the rest, and its first week for anything before it
+pub fn extend(models: &[WeekModel], from: f64, end: f64) -> Vec<WeekModel> {
+    let mut extended = models.to_vec();
+    while let Some(first) = extended.first().cloned() {
+        if first.start <= from {
+            break;
+        }
+        extended.insert(0, WeekModel { start: first.start - 7.0, ..first });
+    }
+    while let Some(last) = extended.last().cloned() {
+        if last.start + 7.0 > end {
+            break;
+        }
+        extended.push(WeekModel { start: last.start + 7.0, ..last });
+    }
+    extended
+}
+
+// Gross irrigation added up day by day for one season of weekly rain (inches, one per model)
+pub fn need(settings: &BalanceSettings, profile: &[SoilLayer], start: &Start, models: &[WeekModel], rain: &[f64], efficiency: f64) -> Vec<(f64, f64)> {
+    let mut depletion = start.depletion;
+    let mut total = 0.0;
+    let mut day = start.today + 1.0;
+    let mut cumulative = Vec::new();
+
+    while day <= season_end(start.today) {
+        // Weekly totals are spread evenly over the week
+        let rain = models.iter().zip(rain)
+            .find(|(model, _)| model.start <= day && day < model.start + 7.0)
+            .map(|(_, amount)| amount / 7.0)
+            .unwrap_or(0.0);
+        let mut input = DayInput { day, et: start.et, rain, ..DayInput::default() };
+
+        let mut dry = depletion;
+        let balance = water_balance::step(settings, profile, &input, &mut dry);
+        if balance.depletion > balance.raw {
+            input.irrigation = balance.depletion - balance.raw;
+            total += input.irrigation / efficiency.max(0.01);
+        }
+        water_balance::step(settings, profile, &input, &mut depletion);
+
+        cumulative.push((day, total));
+        day += 1.0;
+    }
+    cumulative
+}
+
+// Water still needed for `count` seeded seasons, one cumulative curve per season
+pub fn seasons(settings: &BalanceSettings, profile: &[SoilLayer], start: &Start, models: &[WeekModel], seed: u64, count: usize, efficiency: f64) -> Vec<Vec<(f64, f64)>> {
+    let models = extend(models, start.today + 1.0, season_end(start.today));
+    rain_generator::seasons(&models, seed, count)
+        .iter()
+        .map(|rain| need(settings, profile, start, &models, rain, efficiency))
+        .collect()
+}
+
+// One percentile of the cumulative need on every day
+pub fn fan(seasons: &[Vec<(f64, f64)>], percent: f64) -> Vec<(f64, f64)> {
+    let Some(first) = seasons.first() else {
+        return Vec::new();
+    };
+    first.iter().enumerate()
+        .map(|(index, (day, _))| {
+            let totals: Vec<f64> = seasons.iter().filter_map(|season| season.get(index).map(|(_, total)| *total)).collect();
+            (*day, rain_generator::percentile(&totals, percent))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soil;
+    use crate::rainfall::WeeklyRain;
+    use crate::water_balance::EtSource;
+
+    fn setup() -> (BalanceSettings, Vec<SoilLayer>, Start, Vec<WeekModel>) {
+        let settings = BalanceSettings { et_source: EtSource::Reference, ..BalanceSettings::default() };
+        let profile = soil::silt_loam();
+        let today = dates::from_ymd(2024, 7, 15);
+        let start = Start { today, depletion: 1.0, et: 0.25 };
+        let weeks: Vec<WeekModel> = (0..8)
+            .map(|index| {
+                let start = today + 7.0 * index as f64;
+                rain_generator::fit(&WeeklyRain { date_range: String::new(), start, end: start + 6.0, median: 0.6, q1: 0.2, q3: 1.3, proportion_zero: 0.2 })
+            })
+            .collect();
+        (settings, profile, start, weeks)
+    }
+
+    #[test]
+    fn need_adds_up_to_season_end() {
+        let (settings, profile, start, models) = setup();
+        let seasons = seasons(&settings, &profile, &start, &models, 1, 20, 0.85);
+        assert_eq!(seasons.len(), 20);
+        for season in &seasons {
+            assert_eq!(season.last().map(|(day, _)| *day), Some(season_end(start.today)));
+            assert!(season.windows(2).all(|pair| pair[1].1 >= pair[0].1));
+        }
+        // The middle of the fan sits between the wet and the dry end
+        let end = |percent| fan(&seasons, percent).last().map(|(_, total)| *total).unwrap_or(0.0);
+        assert!(end(10.0) <= end(50.0) && end(50.0) <= end(90.0));
+    }
+
+    #[test]
+    fn blank_efficiency_stays_finite() {
+        let (settings, profile, start, models) = setup();
+        let models = extend(&models, start.today + 1.0, season_end(start.today));
+        let need = need(&settings, &profile, &start, &models, &vec![0.0; models.len()], 0.0);
+        assert!(need.iter().all(|(_, total)| total.is_finite()));
+    }
+
+    #[test]
+    fn first_week_stands_in_before_the_file_starts() {
+        let (settings, profile, start, models) = setup();
+        // The file only starts three weeks out, a wet first week has to cover those too
+        let late: Vec<WeekModel> = models[3..].to_vec();
+        let models = extend(&late, start.today + 1.0, season_end(start.today));
+        assert!(models[0].start <= start.today + 1.0);
+        assert_eq!(models[0].proportion_zero, late[0].proportion_zero);
+        let need = need(&settings, &profile, &start, &models, &vec![7.0; models.len()], 0.85);
+        assert_eq!(need.last().map(|(_, total)| *total), Some(0.0));
+    }
+}
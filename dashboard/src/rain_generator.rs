@@ -0,0 +1,183 @@
+// Monte Carlo weekly rainfall from the statistics in simulated_df.xlsx
+//
+// Every week is a zero-inflated lognormal: no rain with the week's proportion_zero, otherwise
+// a lognormal fitted so the quartiles of the whole mix (dry years included) land on the
+// file's Q1, median and Q3. Draws come from a seeded generator so a season can be repeated.
+//
+// Nothing in here touches the browser, so it works the same outside the dashboard.
+
+use crate::rainfall::WeeklyRain;
+
+// How many seasons to draw and where to start the generator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimulationSettings {
+    pub seed: u64,
+    pub seasons: usize,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        SimulationSettings { seed: 2024, seasons: 500 }
+    }
+}
+
+// Zero-inflated lognormal for one week, amounts in inches
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeekModel {
+    pub start: f64, // excel serial of the first day of the week
+    pub proportion_zero: f64,
+    pub mu: f64,    // mean of ln(amount) on wet weeks
+    pub sigma: f64, // standard deviation of ln(amount) on wet weeks
+}
+
+impl WeekModel {
+    // Amount at cumulative probability `u` of the whole mix
+    pub fn quantile(&self, u: f64) -> f64 {
+        if u <= self.proportion_zero {
+            return 0.0;
+        }
+        let wet = ((u - self.proportion_zero) / (1.0 - self.proportion_zero)).clamp(1e-9, 1.0 - 1e-9);
+        (self.mu + self.sigma * inverse_normal(wet)).exp()
+    }
+}
+
+// Fit a week by least squares on ln(quartile) against the wet-only normal score
+//
+// Quartiles that fall in the dry part of the mix say nothing about wet amounts and are
+// skipped. With less than two left the spread falls back to something typical.
+pub fn fit(week: &WeeklyRain) -> WeekModel {
+    let proportion_zero = week.proportion_zero.clamp(0.0, 0.99);
+    let points: Vec<(f64, f64)> = [(0.25, week.q1), (0.5, week.median), (0.75, week.q3)]
+        .into_iter()
+        .filter(|&(u, amount)| u > proportion_zero && amount > 0.0)
+        .map(|(u, amount)| (inverse_normal((u - proportion_zero) / (1.0 - proportion_zero)), amount.ln()))
+        .collect();
+
+    let n = points.len() as f64;
+    let (mu, sigma) = match points.len() {
+        0 => (week.median.max(0.01).ln(), 1.0),
+        1 => (points[0].1 - points[0].0, 1.0),
+        _ => {
+            let mean_z = points.iter().map(|p| p.0).sum::<f64>() / n;
+            let mean_ln = points.iter().map(|p| p.1).sum::<f64>() / n;
+            let covariance: f64 = points.iter().map(|p| (p.0 - mean_z) * (p.1 - mean_ln)).sum();
+            let variance: f64 = points.iter().map(|p| (p.0 - mean_z).powi(2)).sum();
+            let sigma = if variance > 0.0 { (covariance / variance).max(0.05) } else { 1.0 };
+            (mean_ln - sigma * mean_z, sigma)
+        },
+    };
+    WeekModel { start: week.start, proportion_zero, mu, sigma }
+}
+
+// SplitMix64, small and the same on every platform (rand needs extra setup on wasm)
+#[derive(Clone, Debug)]
+pub struct Generator(u64);
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Generator(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1)
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// One season of weekly totals (inches), in the same order as `models`
+pub fn season(models: &[WeekModel], generator: &mut Generator) -> Vec<f64> {
+    models.iter().map(|model| model.quantile(generator.uniform())).collect()
+}
+
+// `count` seasons from one seed, the same seed always gives the same seasons
+pub fn seasons(models: &[WeekModel], seed: u64, count: usize) -> Vec<Vec<f64>> {
+    let mut generator = Generator::new(seed);
+    (0..count).map(|_| season(models, &mut generator)).collect()
+}
+
+// Value at a percentile (0 - 100) of some draws, nearest rank
+pub fn percentile(values: &[f64], percent: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = (percent / 100.0 * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+// Standard normal quantile, Acklam's rational approximation (good to about 1e-9)
+fn inverse_normal(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    const LOW: f64 = 0.02425;
+
+    let p = p.clamp(1e-12, 1.0 - 1e-12);
+    if p < LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -inverse_normal(1.0 - p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn week(start: f64, proportion_zero: f64) -> WeeklyRain {
+        WeeklyRain { date_range: String::new(), start, end: start + 6.0, median: 0.6, q1: 0.2, q3: 1.3, proportion_zero }
+    }
+
+    fn models() -> Vec<WeekModel> {
+        (0..16).map(|index| fit(&week(45453.0 + 7.0 * index as f64, 0.1 + 0.02 * index as f64))).collect()
+    }
+
+    #[test]
+    fn same_seed_same_draws() {
+        let models = models();
+        assert_eq!(seasons(&models, 2024, 50), seasons(&models, 2024, 50));
+        assert_ne!(seasons(&models, 2024, 50), seasons(&models, 2025, 50));
+        // Asking for more seasons doesn't change the ones already drawn
+        assert_eq!(seasons(&models, 7, 10)[..], seasons(&models, 7, 20)[..10]);
+    }
+
+    #[test]
+    fn draws_follow_the_statistics() {
+        let model = fit(&week(45453.0, 0.1));
+        // Three quartiles and two parameters, the fit only gets close
+        for (u, quartile) in [(0.25, 0.2), (0.5, 0.6), (0.75, 1.3)] {
+            assert!((model.quantile(u) / quartile - 1.0).abs() < 0.25, "{u}: {} against {quartile}", model.quantile(u));
+        }
+
+        let draws: Vec<f64> = seasons(std::slice::from_ref(&model), 1, 20_000).into_iter().map(|season| season[0]).collect();
+        let dry = draws.iter().filter(|amount| **amount == 0.0).count() as f64 / draws.len() as f64;
+        assert!((dry - 0.1).abs() < 0.01, "dry share {dry}");
+        for percent in [25.0, 50.0, 75.0] {
+            let expected = model.quantile(percent / 100.0);
+            assert!((percentile(&draws, percent) / expected - 1.0).abs() < 0.05, "P{percent}: {} against {expected}", percentile(&draws, percent));
+        }
+        assert!(draws.iter().all(|amount| amount.is_finite() && *amount >= 0.0));
+    }
+
+    #[test]
+    fn inverse_normal_known_values() {
+        assert!(inverse_normal(0.5).abs() < 1e-9);
+        assert!((inverse_normal(0.975) - 1.959964).abs() < 1e-6);
+        assert!((inverse_normal(0.025) + 1.959964).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,110 @@
+// Weekly rainfall statistics from simulated_df.xlsx
+//
+// The sheet has one row per simulated draw, and every row repeats the statistics of its
+// week ("June 10-16" through "Sep 2-8"). Amounts in the file are mm, we keep inches.
+
+use calamine::{Data, Range};
+
+use crate::data;
+use crate::dates;
+
+pub const FILE: &str = "simulated_df.xlsx";
+pub const SHEET: &str = "Sheet 1";
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+
+// Rainfall statistics for one week of the season
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeeklyRain {
+    pub date_range: String,
+    pub start: f64, // excel serial of the first day
+    pub end: f64,   // excel serial of the last day
+    // Quartiles in inches over every year, the dry ones included
+    pub median: f64,
+    pub q1: f64,
+    pub q3: f64,
+    pub proportion_zero: f64, // share of years this week got no rain at all
+}
+
+impl WeeklyRain {
+    // Chance that it rains at all this week
+    pub fn chance(&self) -> f64 {
+        1.0 - self.proportion_zero
+    }
+
+    pub fn contains(&self, day: f64) -> bool {
+        self.start <= day.floor() && day.floor() <= self.end
+    }
+}
+
+fn month(name: &str) -> Option<u32> {
+    let name = name.trim().to_lowercase();
+    MONTHS.iter().position(|month| name.starts_with(month)).map(|index| index as u32 + 1)
+}
+
+// Turn "June 10-16" or "July 29-Aug 4" into the first and last day of that week
+pub fn parse_week(date_range: &str, year: i64) -> Option<(f64, f64)> {
+    let (first, last) = date_range.split_once('-')?;
+    let (first_month, first_day) = first.trim().split_once(' ')?;
+    let first_month = month(first_month)?;
+    let (last_month, last_day) = match last.trim().split_once(' ') {
+        Some((last_month, last_day)) => (month(last_month)?, last_day),
+        None => (first_month, last.trim()),
+    };
+    Some((
+        dates::from_ymd(year, first_month, first_day.trim().parse().ok()?),
+        dates::from_ymd(year, last_month, last_day.trim().parse().ok()?),
+    ))
+}
+
+// One entry per week, in season order
+pub fn weeks(range: &Range<Data>, year: i64) -> Vec<WeeklyRain> {
+    let mut weeks: Vec<WeeklyRain> = Vec::new();
+    for row in range.rows().skip(1) {
+        let Some(Data::String(date_range)) = row.first() else { continue };
+        if weeks.iter().any(|week| &week.date_range == date_range) {
+            continue;
+        }
+        let Some((start, end)) = parse_week(date_range, year) else { continue };
+        let number = |column: usize| row.get(column).and_then(data::cell_f64);
+        let (Some(median), Some(q1), Some(q3), Some(proportion_zero)) = (number(1), number(2), number(3), number(4)) else { continue };
+        weeks.push(WeeklyRain {
+            date_range: date_range.clone(),
+            start,
+            end,
+            median: median / 25.4,
+            q1: q1 / 25.4,
+            q3: q3 / 25.4,
+            proportion_zero,
+        });
+    }
+    weeks.sort_by(|a, b| a.start.total_cmp(&b.start));
+    weeks
+}
+
+// Week a day falls in, if the file covers it
+pub fn week_for(weeks: &[WeeklyRain], day: f64) -> Option<&WeeklyRain> {
+    weeks.iter().find(|week| week.contains(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_week_within_and_across_months() {
+        assert_eq!(parse_week("June 10-16", 2024), Some((dates::from_ymd(2024, 6, 10), dates::from_ymd(2024, 6, 16))));
+        assert_eq!(parse_week("July 29-Aug 4", 2024), Some((dates::from_ymd(2024, 7, 29), dates::from_ymd(2024, 8, 4))));
+        assert_eq!(parse_week("Sept 2 - 8", 2024), Some((dates::from_ymd(2024, 9, 2), dates::from_ymd(2024, 9, 8))));
+        assert_eq!(parse_week("Week 23", 2024), None);
+    }
+
+    #[test]
+    fn week_for_a_day() {
+        let (start, end) = parse_week("July 1-7", 2024).unwrap();
+        let weeks = [WeeklyRain { date_range: "July 1-7".to_string(), start, end, median: 0.5, q1: 0.1, q3: 1.0, proportion_zero: 0.3 }];
+        assert!(week_for(&weeks, end + 0.5).is_some());
+        assert!(week_for(&weeks, end + 1.0).is_none());
+        assert!((weeks[0].chance() - 0.7).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,167 @@
+// Irrigation recommendations for the next week
+//
+// Projects the root zone forward a day at a time from today's depletion and plans pivot
+// passes so depletion never crosses the readily available water (RAW). A pivot can only put
+// down so much a day, so it is started one day of capacity ahead of that line.
+
+use crate::dates;
+use crate::rainfall::{self, WeeklyRain};
+
+// How many days the plan looks ahead
+pub const DAYS: usize = 7;
+
+// Room left below field capacity when refilling, so a rain isn't wasted
+const RAIN_ROOM: f64 = 0.5;
+
+// Passes smaller than this aren't worth starting the pivot for
+const MIN_PASS: f64 = 0.1;
+
+// What the pivot can do
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pivot {
+    pub capacity: f64,   // gross inches a day with the pivot running non stop
+    pub efficiency: f64, // share of the gross application that makes it into the root zone
+}
+
+impl Default for Pivot {
+    fn default() -> Self {
+        // About 800 gpm on a 125 acre circle
+        Pivot { capacity: 0.3, efficiency: 0.85 }
+    }
+}
+
+impl Pivot {
+    // Inches a day that actually end up in the soil
+    pub fn net_capacity(&self) -> f64 {
+        self.capacity * self.efficiency.max(0.01)
+    }
+}
+
+// Where the root zone is today and what the weather looks like
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outlook {
+    pub today: f64, // excel serial
+    pub depletion: f64,
+    pub taw: f64,
+    pub raw: f64,
+    pub et: f64, // forecast crop ET for each of the coming days
+}
+
+// One day of the plan
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlannedDay {
+    pub day: f64,
+    pub et: f64,
+    pub rain: f64,       // expected rain counted for the day
+    pub irrigation: f64, // gross inches to apply
+    pub depletion: f64,  // end of day depletion following the plan
+    pub unirrigated: f64, // end of day depletion if the pivot stays off
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recommendation {
+    pub plan: Vec<PlannedDay>,
+    pub reasons: Vec<String>,
+}
+
+// Rain to count on for a day: the week's median spread over it
+//
+// The quartiles already take the dry years in, so the chance of rain isn't applied on top.
+pub fn expected_rain(weeks: &[WeeklyRain], day: f64) -> f64 {
+    rainfall::week_for(weeks, day).map(|week| week.median / 7.0).unwrap_or(0.0)
+}
+
+pub fn recommend(outlook: &Outlook, pivot: &Pivot, weeks: &[WeeklyRain]) -> Recommendation {
+    // Nothing gets divided by a blank efficiency
+    let efficiency = pivot.efficiency.max(0.01);
+    let net_capacity = pivot.net_capacity();
+    let trigger = (outlook.raw - net_capacity).max(0.0);
+    let target = RAIN_ROOM.min(trigger);
+
+    let mut reasons = vec![
+        format!("Root zone is {:.2} in below field capacity, the crop starts to stress past {:.2} in (RAW) and has {:.2} in in total (TAW).", outlook.depletion, outlook.raw, outlook.taw),
+        format!("Crop ET is forecast at {:.2} in/day (average of the last week).", outlook.et),
+        format!("The pivot puts down {:.2} in/day gross, {:.2} in/day net at {:.0}% efficiency, so passes start once depletion would pass {:.2} in.", pivot.capacity, net_capacity, efficiency * 100.0, trigger),
+    ];
+    match rainfall::week_for(weeks, outlook.today) {
+        Some(week) => reasons.push(format!("{}: {:.0}% chance of rain, median {:.2} in dry years included (Q1 {:.2}, Q3 {:.2}), counting {:.2} in/day.", week.date_range, week.chance() * 100.0, week.median, week.q1, week.q3, expected_rain(weeks, outlook.today))),
+        None => reasons.push("No rainfall statistics for this week, no rain is counted on.".to_string()),
+    }
+    if outlook.et - expected_rain(weeks, outlook.today) > net_capacity {
+        reasons.push("The crop is using more than the pivot can put back, it needs to run non stop to keep up.".to_string());
+    }
+
+    let mut depletion = outlook.depletion;
+    let mut unirrigated = outlook.depletion;
+    let mut plan = Vec::with_capacity(DAYS);
+
+    for offset in 1..=DAYS {
+        let day = outlook.today + offset as f64;
+        let rain = expected_rain(weeks, day);
+        let projected = (depletion + outlook.et - rain).clamp(0.0, outlook.taw);
+        unirrigated = (unirrigated + outlook.et - rain).clamp(0.0, outlook.taw);
+
+        let mut irrigation = 0.0;
+        if projected > trigger {
+            // Fill back up to the target, as far as one day of pivot time allows
+            let net = (projected - target).min(net_capacity);
+            if net >= MIN_PASS * efficiency {
+                irrigation = net / efficiency;
+                reasons.push(format!("{}: depletion would reach {:.2} in, past the {:.2} in trigger, apply {:.2} in gross.", dates::label(day), projected, trigger, irrigation));
+            }
+        }
+        depletion = (projected - irrigation * efficiency).max(0.0);
+
+        plan.push(PlannedDay { day, et: outlook.et, rain, irrigation, depletion, unirrigated });
+    }
+
+    let total: f64 = plan.iter().map(|day| day.irrigation).sum();
+    if total > 0.0 {
+        reasons.push(format!("Plan: {:.2} in gross over the next {} days, ending {:.2} in below field capacity.", total, DAYS, depletion));
+    } else {
+        reasons.push(format!("No irrigation needed in the next {} days, depletion ends at {:.2} in.", DAYS, depletion));
+    }
+    if unirrigated > outlook.raw {
+        reasons.push(format!("Without irrigation the crop would be stressed, depletion would reach {:.2} in.", unirrigated));
+    }
+
+    Recommendation { plan, reasons }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outlook() -> Outlook {
+        Outlook { today: 45474.0, depletion: 1.5, taw: 4.0, raw: 2.0, et: 0.3 }
+    }
+
+    #[test]
+    fn plan_keeps_depletion_under_raw() {
+        let pivot = Pivot { capacity: 0.5, efficiency: 0.85 };
+        let recommendation = recommend(&outlook(), &pivot, &[]);
+        assert_eq!(recommendation.plan.len(), DAYS);
+        assert!(recommendation.plan.iter().any(|day| day.irrigation > 0.0));
+        for day in &recommendation.plan {
+            assert!(day.depletion <= outlook().raw + 1e-9, "{day:?}");
+            assert!(day.irrigation <= pivot.capacity + 1e-9);
+        }
+        // Without the pivot the week would end well past RAW
+        assert!((recommendation.plan[DAYS - 1].unirrigated - (1.5 + 0.3 * DAYS as f64)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn median_already_has_the_dry_years_in() {
+        let week = WeeklyRain { date_range: String::new(), start: 45474.0, end: 45480.0, median: 0.7, q1: 0.0, q3: 1.4, proportion_zero: 0.4 };
+        assert!((expected_rain(&[week], 45476.0) - 0.1).abs() < 1e-9);
+        assert_eq!(expected_rain(&[], 45476.0), 0.0);
+    }
+
+    #[test]
+    fn blank_efficiency_stays_finite() {
+        let recommendation = recommend(&outlook(), &Pivot { capacity: 0.3, efficiency: 0.0 }, &[]);
+        for day in &recommendation.plan {
+            assert!(day.irrigation.is_finite() && day.depletion.is_finite(), "{day:?}");
+        }
+    }
+}
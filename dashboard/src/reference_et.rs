@@ -0,0 +1,164 @@
+// ASCE standardized reference evapotranspiration (ASCE-EWRI 2005, daily time step)
+//
+// Worked out from the weather the Arables record. Returned in inches a day to match the
+// Arable ET columns.
+
+use std::f64::consts::PI;
+
+use crate::data::arable;
+use crate::dates;
+
+// Where the TAPS field is (KSU Northwest Research-Extension Center, Colby)
+const LATITUDE: f64 = 39.39; // degrees north
+const ELEVATION: f64 = 960.0; // m
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Surface {
+    Short, // ETo, clipped grass
+    Tall,  // ETr, alfalfa
+}
+
+impl Surface {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Surface::Short => "ETo (grass)",
+            Surface::Tall => "ETr (alfalfa)",
+        }
+    }
+
+    // Numerator and denominator constants (Cn, Cd) from table 1
+    fn constants(&self) -> (f64, f64) {
+        match self {
+            Surface::Short => (900.0, 0.34),
+            Surface::Tall => (1600.0, 0.38),
+        }
+    }
+}
+
+// One day of Arable weather, in Arable's units
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weather {
+    pub day: f64,              // excel serial
+    pub max_temp: f64,         // F
+    pub min_temp: f64,         // F
+    pub rh_at_max_temp: f64,   // %, the day's lowest humidity
+    pub rh_at_min_temp: f64,   // %, the day's highest humidity
+    pub radiation: f64,        // shortwave, MJ/m2/day
+    pub wind: f64,             // mph, taken as the 2 m speed
+}
+
+// Arable columns `weather` expects, in order
+pub const COLUMNS: [usize; 6] = [arable::MAX_TEMP, arable::MIN_TEMP, arable::RH_AT_MAX_TEMP, arable::RH_AT_MIN_TEMP, arable::SHORTWAVE, arable::WIND_SPEED];
+
+// Days with every reading, from rows read with COLUMNS
+pub fn weather(rows: &[(f64, Vec<Option<f64>>)]) -> Vec<Weather> {
+    rows.iter()
+        .filter_map(|(day, values)| Some(Weather {
+            day: day.floor(),
+            max_temp: values[0]?,
+            min_temp: values[1]?,
+            rh_at_max_temp: values[2]?,
+            rh_at_min_temp: values[3]?,
+            radiation: values[4]?,
+            wind: values[5]?,
+        }))
+        .collect()
+}
+
+pub fn celsius(fahrenheit: f64) -> f64 {
+    (fahrenheit - 32.0) * 5.0 / 9.0
+}
+
+// Saturation vapour pressure (kPa) at a temperature in C
+pub fn saturation_vapour_pressure(temperature: f64) -> f64 {
+    0.6108 * (17.27 * temperature / (temperature + 237.3)).exp()
+}
+
+// Extraterrestrial radiation (MJ/m2/day) at a latitude in degrees north
+fn extraterrestrial_radiation(day: f64, latitude: f64) -> f64 {
+    let (year, _, _) = dates::to_ymd(day);
+    let day_of_year = day - dates::from_ymd(year, 1, 1) + 1.0;
+    let latitude = latitude.to_radians();
+
+    let inverse_distance = 1.0 + 0.033 * (2.0 * PI / 365.0 * day_of_year).cos();
+    let declination = 0.409 * (2.0 * PI / 365.0 * day_of_year - 1.39).sin();
+    let sunset = (-latitude.tan() * declination.tan()).clamp(-1.0, 1.0).acos();
+    24.0 * 60.0 / PI * 0.0820 * inverse_distance
+        * (sunset * latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * sunset.sin())
+}
+
+// Reference ET (inches) for one day
+pub fn daily(weather: &Weather, surface: Surface) -> f64 {
+    daily_at(weather, surface, LATITUDE, ELEVATION)
+}
+
+// Reference ET (inches) for one day somewhere else than the field (latitude in degrees, elevation in m)
+fn daily_at(weather: &Weather, surface: Surface, latitude: f64, elevation: f64) -> f64 {
+    let max = celsius(weather.max_temp);
+    let min = celsius(weather.min_temp);
+    let mean = (max + min) / 2.0;
+    let wind = weather.wind * 0.44704; // m/s
+
+    let pressure = 101.3 * ((293.0 - 0.0065 * elevation) / 293.0).powf(5.26);
+    let psychrometric = 0.000665 * pressure;
+    let slope = 2503.0 * (17.27 * mean / (mean + 237.3)).exp() / (mean + 237.3).powi(2);
+
+    let saturation = (saturation_vapour_pressure(max) + saturation_vapour_pressure(min)) / 2.0;
+    let actual = (saturation_vapour_pressure(min) * weather.rh_at_min_temp.clamp(0.0, 100.0) / 100.0
+        + saturation_vapour_pressure(max) * weather.rh_at_max_temp.clamp(0.0, 100.0) / 100.0) / 2.0;
+
+    // Net radiation, the soil heat flux is taken as 0 on a daily step
+    let clear_sky = (0.75 + 2e-5 * elevation) * extraterrestrial_radiation(weather.day, latitude);
+    let relative = if clear_sky > 0.0 { (weather.radiation / clear_sky).clamp(0.3, 1.0) } else { 1.0 };
+    let net_shortwave = (1.0 - 0.23) * weather.radiation;
+    let net_longwave = 4.901e-9 * ((max + 273.16).powi(4) + (min + 273.16).powi(4)) / 2.0
+        * (0.34 - 0.14 * actual.max(0.0).sqrt())
+        * (1.35 * relative - 0.35);
+    let net = net_shortwave - net_longwave;
+
+    let (numerator, denominator) = surface.constants();
+    let millimetres = (0.408 * slope * net + psychrometric * numerator / (mean + 273.0) * wind * (saturation - actual))
+        / (slope + psychrometric * (1.0 + denominator * wind));
+    millimetres.max(0.0) / 25.4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FAO-56 example 18, Brussels on July 6th (50 48' N, 100 m)
+    fn brussels() -> Weather {
+        let fahrenheit = |celsius: f64| celsius * 9.0 / 5.0 + 32.0;
+        Weather {
+            day: dates::from_ymd(2023, 7, 6),
+            max_temp: fahrenheit(21.5),
+            min_temp: fahrenheit(12.3),
+            rh_at_max_temp: 63.0,
+            rh_at_min_temp: 84.0,
+            radiation: 22.07,
+            wind: 2.078 / 0.44704,
+        }
+    }
+
+    #[test]
+    fn fao_56_example_18() {
+        let latitude = 50.0 + 48.0 / 60.0;
+        assert!((extraterrestrial_radiation(brussels().day, latitude) - 41.09).abs() < 0.1);
+        let eto = daily_at(&brussels(), Surface::Short, latitude, 100.0) * 25.4;
+        assert!((eto - 3.9).abs() < 0.1, "{eto}");
+        // Alfalfa is rougher and taller, it uses more
+        assert!(daily_at(&brussels(), Surface::Tall, latitude, 100.0) * 25.4 > eto);
+    }
+
+    #[test]
+    fn missing_readings_drop_the_day() {
+        let rows = vec![
+            (45474.5, vec![Some(90.0), Some(60.0), Some(30.0), Some(80.0), Some(28.0), Some(5.0)]),
+            (45475.5, vec![Some(90.0), Some(60.0), None, Some(80.0), Some(28.0), Some(5.0)]),
+        ];
+        let days = weather(&rows);
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].day, 45474.0);
+        assert!(daily(&days[0], Surface::Short) > 0.0);
+    }
+}
@@ -0,0 +1,115 @@
+// Where the roots are pulling water from, read off the AquaSpy sensors
+//
+// Roots only take water up while the crop is transpiring, so a depth the roots are working
+// steps down through the day and sits flat overnight. Drainage and redistribution go on
+// around the clock, they don't have that pattern. Timestamps are taken as local time.
+
+use crate::data::aquaspy;
+use crate::drawdown;
+use crate::events::WettingEvent;
+
+// Daytime, as a fraction of the day
+const DAY_START: f64 = 8.0 / 24.0;
+const DAY_END: f64 = 20.0 / 24.0;
+// Furthest (days) a reading can be from the time it stands in for
+const NEAREST: f64 = 1.5 / 24.0;
+// Smallest daytime drop (volumetric % points) that counts as uptake
+const MIN_DROP: f64 = 0.1;
+// Daytime drop has to be this many times the overnight one
+const DAY_NIGHT: f64 = 2.0;
+// Days the effective depth is taken over, roots don't pull from every depth every day
+pub const WINDOW: usize = 5;
+
+fn reading_at(moisture: &[(f64, Vec<Option<f64>>)], time: f64, sensor: usize) -> Option<f64> {
+    moisture.iter()
+        .filter(|(other, _)| (other - time).abs() <= NEAREST)
+        .min_by(|a, b| (a.0 - time).abs().total_cmp(&(b.0 - time).abs()))
+        .and_then(|(_, readings)| readings[sensor])
+}
+
+// Depths (inches) that show daytime uptake, for every undisturbed day (readings in time order)
+pub fn active_depths(moisture: &[(f64, Vec<Option<f64>>)], events: &[WettingEvent]) -> Vec<(f64, Vec<f64>)> {
+    let (Some(first), Some(last)) = (moisture.first(), moisture.last()) else {
+        return Vec::new();
+    };
+    let mut days = Vec::new();
+    let mut day = first.0.floor();
+    while day < last.0.floor() {
+        if !drawdown::disturbed(events, day) {
+            let depths = aquaspy::DEPTHS.iter().enumerate()
+                .filter(|&(sensor, _)| {
+                    let morning = reading_at(moisture, day + DAY_START, sensor);
+                    let evening = reading_at(moisture, day + DAY_END, sensor);
+                    let next_morning = reading_at(moisture, day + 1.0 + DAY_START, sensor);
+                    match (morning, evening, next_morning) {
+                        (Some(morning), Some(evening), Some(next_morning)) => {
+                            let daytime = morning - evening;
+                            let overnight = (evening - next_morning).max(0.0);
+                            daytime >= MIN_DROP && daytime >= DAY_NIGHT * overnight
+                        },
+                        _ => false,
+                    }
+                })
+                .map(|(_, depth)| *depth)
+                .collect();
+            days.push((day, depths));
+        }
+        day += 1.0;
+    }
+    days
+}
+
+// Deepest active sensor on every day
+pub fn deepest(active: &[(f64, Vec<f64>)]) -> Vec<(f64, f64)> {
+    active.iter()
+        .map(|(day, depths)| (*day, depths.iter().copied().fold(0.0, f64::max)))
+        .collect()
+}
+
+// Deepest sensor active in the last WINDOW days, steadier than any one day
+pub fn effective_depth(active: &[(f64, Vec<f64>)]) -> Vec<(f64, f64)> {
+    let deepest = deepest(active);
+    deepest.iter().enumerate()
+        .map(|(index, (day, _))| {
+            let window = &deepest[index.saturating_sub(WINDOW - 1)..=index];
+            (*day, window.iter().map(|(_, depth)| *depth).fold(0.0, f64::max))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Three days of hourly readings: down to 24 in the sensors drop in the daytime only, below
+    // that they drain at the same pace day and night
+    fn moisture() -> Vec<(f64, Vec<Option<f64>>)> {
+        (0..3 * 24)
+            .map(|hour| {
+                let time = 45474.0 + hour as f64 / 24.0;
+                let daytime_hours: f64 = (0..hour).filter(|hour| (8..20).contains(&(hour % 24))).count() as f64;
+                let readings = aquaspy::DEPTHS.iter()
+                    .map(|&depth| Some(if depth <= 24.0 { 30.0 - 0.05 * daytime_hours } else { 30.0 - 0.02 * hour as f64 }))
+                    .collect();
+                (time, readings)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn daytime_uptake_marks_the_roots() {
+        let active = active_depths(&moisture(), &[]);
+        assert_eq!(active.len(), 2);
+        assert!(active.iter().all(|(_, depths)| *depths == vec![4.0, 8.0, 12.0, 16.0, 20.0, 24.0]));
+        assert_eq!(deepest(&active), vec![(45474.0, 24.0), (45475.0, 24.0)]);
+    }
+
+    #[test]
+    fn effective_depth_holds_the_deepest_over_the_window() {
+        let active = vec![(1.0, vec![4.0, 28.0]), (2.0, vec![4.0]), (3.0, vec![]), (4.0, vec![12.0])];
+        assert_eq!(effective_depth(&active), vec![(1.0, 28.0), (2.0, 28.0), (3.0, 28.0), (4.0, 28.0)]);
+        let later: Vec<(f64, Vec<f64>)> = (0..WINDOW).map(|day| (5.0 + day as f64, vec![8.0])).collect();
+        let active: Vec<(f64, Vec<f64>)> = active.into_iter().chain(later).collect();
+        assert_eq!(effective_depth(&active).last(), Some(&(9.0, 8.0)));
+    }
+}
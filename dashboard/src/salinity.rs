@@ -0,0 +1,125 @@
+// Pore-water EC from the bulk EC the AquaSpy reads
+//
+// Bulk EC goes up and down with moisture as much as with salts, so on its own it can't show
+// fertilizer or salt moving. Hilhorst (2000) takes the moisture out:
+//
+//     EC pore = permittivity of pore water * EC bulk / (bulk permittivity - 4.1)
+//
+// Bulk permittivity comes from the volumetric moisture by turning Topp's equation around, and
+// everything is brought to 25 C (USDA Handbook 60 ratio) so warm afternoons don't look salty.
+
+// Hilhorst's offset, bulk permittivity where the bulk EC goes to 0
+const OFFSET: f64 = 4.1;
+// Below this bulk permittivity (about 10% moisture) the model falls apart
+const DRIEST: f64 = 6.0;
+
+fn celsius(fahrenheit: f64) -> f64 {
+    (fahrenheit - 32.0) * 5.0 / 9.0
+}
+
+// Topp et al. (1980), moisture (fraction) from bulk permittivity
+fn topp(permittivity: f64) -> f64 {
+    -5.3e-2 + 2.92e-2 * permittivity - 5.5e-4 * permittivity.powi(2) + 4.3e-6 * permittivity.powi(3)
+}
+
+// Bulk permittivity for a moisture (fraction), Topp is increasing over 1 - 50 so bisect it
+pub fn bulk_permittivity(moisture: f64) -> f64 {
+    let (mut low, mut high) = (1.0, 50.0);
+    for _ in 0..40 {
+        let middle = (low + high) / 2.0;
+        if topp(middle) < moisture {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+// Permittivity of water at a temperature (C)
+fn water_permittivity(temperature: f64) -> f64 {
+    80.3 - 0.37 * (temperature - 20.0)
+}
+
+// Factor that brings EC read at a temperature (C) to 25 C
+fn to_25c(temperature: f64) -> f64 {
+    0.447 + 1.4034 * (-temperature / 26.815).exp()
+}
+
+// Pore-water EC at 25 C from bulk EC, moisture (volumetric %) and temperature (F),
+// None when the soil is too dry for the model
+pub fn pore_ec(bulk: f64, moisture: f64, temperature: f64) -> Option<f64> {
+    let temperature = celsius(temperature);
+    let permittivity = bulk_permittivity(moisture / 100.0);
+    if permittivity <= DRIEST {
+        return None;
+    }
+    Some(water_permittivity(temperature) * bulk / (permittivity - OFFSET) * to_25c(temperature))
+}
+
+// Pore EC per depth for every reading, columns in sensor order
+// (moisture, EC and temperature rows all have one value per sensor)
+pub fn pore_series(moisture: &[Option<f64>], ec: &[Option<f64>], temperature: &[Option<f64>]) -> Vec<Option<f64>> {
+    moisture.iter().zip(ec).zip(temperature)
+        .map(|((moisture, ec), temperature)| pore_ec((*ec)?, (*moisture)?, (*temperature)?))
+        .collect()
+}
+
+// Days where a depth jumped more than `jump` (fraction) over the median of the week before,
+// (day, sensor index, how much)
+pub fn pulses(daily: &[(f64, Vec<Option<f64>>)], jump: f64) -> Vec<(f64, usize, f64)> {
+    let mut found = Vec::new();
+    for (index, (day, values)) in daily.iter().enumerate() {
+        for (sensor, value) in values.iter().enumerate() {
+            let Some(value) = value else { continue };
+            let mut before: Vec<f64> = daily[index.saturating_sub(7)..index].iter().filter_map(|(_, values)| values[sensor]).collect();
+            if before.len() < 3 {
+                continue;
+            }
+            before.sort_by(f64::total_cmp);
+            let median = before[before.len() / 2];
+            // Only the first day of a pulse
+            let already = found.iter().any(|&(other, other_sensor, _): &(f64, usize, f64)| other_sensor == sensor && day - other <= 7.0);
+            if median > 0.0 && value / median - 1.0 > jump && !already {
+                found.push((*day, sensor, value / median - 1.0));
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topp_turned_around() {
+        for permittivity in [8.0, 15.0, 25.0, 40.0] {
+            assert!((bulk_permittivity(topp(permittivity)) - permittivity).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn hilhorst_pore_ec() {
+        // Bulk permittivity of 20 is about 34.5% moisture, at 25 C water's is 78.45
+        let moisture = topp(20.0) * 100.0;
+        let pore = pore_ec(0.5, moisture, 77.0).unwrap();
+        assert!((pore - 78.45 * 0.5 / (20.0 - 4.1) * to_25c(25.0)).abs() < 1e-6);
+        assert!((to_25c(25.0) - 1.0).abs() < 0.001);
+        // Same bulk EC in wetter soil is less salty water, warmer readings come down to 25 C
+        assert!(pore_ec(0.5, moisture + 5.0, 77.0).unwrap() < pore);
+        assert!(pore_ec(0.5, moisture, 95.0).unwrap() < pore);
+        assert_eq!(pore_ec(0.5, 5.0, 77.0), None);
+    }
+
+    #[test]
+    fn first_day_of_a_pulse() {
+        let daily: Vec<(f64, Vec<Option<f64>>)> = [1.0, 1.1, 0.9, 1.0, 1.6, 1.7, 1.0].iter().enumerate()
+            .map(|(day, value)| (day as f64, vec![Some(*value), Some(1.0)]))
+            .collect();
+        let found = pulses(&daily, 0.3);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].0, found[0].1), (4.0, 0));
+        assert!((found[0].2 - 0.6).abs() < 1e-9);
+    }
+}
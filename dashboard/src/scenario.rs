@@ -0,0 +1,245 @@
+// What-if irrigation schedules replayed through the water balance
+//
+// Every scenario runs the recorded season (ET, and rain unless it's drawn) again, irrigating
+// the way the team did up to `from` and the scenario's way after that. `DayInput` irrigation is
+// net, what got into the root zone, like the water balance counts it. Every schedule's water
+// is added up gross though, the pivot's efficiency is what turns one into the other.
+
+use crate::rain_generator::{self, WeekModel};
+use crate::soil::SoilLayer;
+use crate::water_balance::{self, BalanceSettings, DayInput};
+
+// Day names for the weekly schedule, Monday first
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+// 0 for Monday up to 6 for Sunday (excel serial 2 is a Monday)
+pub fn weekday(day: f64) -> usize {
+    (day - 2.0).rem_euclid(7.0) as usize
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Schedule {
+    Logged,  // what's in the irrigation log
+    Rainfed, // nothing at all
+    Weekly,  // the same amount on the same weekdays every week
+    Refill,  // back up to readily available water whenever it would be passed
+}
+
+impl Schedule {
+    pub const ALL: [Schedule; 4] = [Schedule::Logged, Schedule::Rainfed, Schedule::Weekly, Schedule::Refill];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Schedule::Logged => "As logged",
+            Schedule::Rainfed => "No irrigation",
+            Schedule::Weekly => "Fixed weekly",
+            Schedule::Refill => "Refill at RAW",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Schedule> {
+        Schedule::ALL.into_iter().find(|schedule| schedule.name() == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    pub name: String,
+    pub schedule: Schedule,
+    pub amount: f64,         // gross inches a pass (Weekly)
+    pub weekdays: [bool; 7], // days the pivot runs (Weekly), Monday first
+}
+
+impl Scenario {
+    pub fn weekly(name: &str, amount: f64, days: &[usize]) -> Self {
+        let mut weekdays = [false; 7];
+        for &day in days {
+            weekdays[day] = true;
+        }
+        Scenario { name: name.to_string(), schedule: Schedule::Weekly, amount, weekdays }
+    }
+
+    // What the editor starts with, the log against the two usual ways of putting an inch on
+    pub fn defaults() -> Vec<Scenario> {
+        vec![
+            Scenario { name: "As logged".to_string(), schedule: Schedule::Logged, amount: 0.0, weekdays: [false; 7] },
+            Scenario::weekly("1 in Monday", 1.0, &[0]),
+            Scenario::weekly("0.5 in twice", 0.5, &[0, 3]),
+        ]
+    }
+}
+
+// Where the rain in the replay comes from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RainSource {
+    Recorded,  // what the Arable caught
+    Simulated, // the rainfall simulator's seasons from `from` on
+}
+
+impl RainSource {
+    pub const ALL: [RainSource; 2] = [RainSource::Recorded, RainSource::Simulated];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RainSource::Recorded => "Recorded rain",
+            RainSource::Simulated => "Simulated rain draws",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<RainSource> {
+        RainSource::ALL.into_iter().find(|source| source.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WhatIfSettings {
+    pub from: f64, // excel serial the scenarios take over from
+    pub rain: RainSource,
+}
+
+// How one scenario went over one season of rain
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outcome {
+    pub depletion: Vec<(f64, f64)>,
+    pub raw: Vec<(f64, f64)>,
+    pub irrigation: f64,  // gross inches
+    pub stress_days: usize,
+    pub drainage: f64,    // inches past the root zone
+    pub et_lost: f64,     // crop ET the stress took away
+}
+
+// Replay the season once, `inputs` carry the rain to use and the irrigation the team put on
+pub fn run(settings: &BalanceSettings, profile: &[SoilLayer], inputs: &[DayInput], initial_depletion: f64,
+           scenario: &Scenario, from: f64, efficiency: f64) -> Outcome {
+    let efficiency = efficiency.max(0.01);
+    let mut depletion = initial_depletion.max(0.0);
+    let mut outcome = Outcome::default();
+    for input in inputs {
+        // (gross, net) inches
+        let (gross, net) = match scenario.schedule {
+            _ if input.day < from => (input.irrigation / efficiency, input.irrigation),
+            Schedule::Logged => (input.irrigation / efficiency, input.irrigation),
+            Schedule::Rainfed => (0.0, 0.0),
+            Schedule::Weekly if scenario.weekdays[weekday(input.day)] => (scenario.amount, scenario.amount * efficiency),
+            Schedule::Weekly => (0.0, 0.0),
+            Schedule::Refill => {
+                // Same as the water need projection, see where the day ends without water first
+                let mut dry = depletion;
+                let balance = water_balance::step(settings, profile, &DayInput { irrigation: 0.0, ..*input }, &mut dry);
+                let net = (balance.depletion - balance.raw).max(0.0);
+                (net / efficiency, net)
+            },
+        };
+
+        let day = water_balance::step(settings, profile, &DayInput { irrigation: net, ..*input }, &mut depletion);
+        outcome.depletion.push((day.day, day.depletion));
+        outcome.raw.push((day.day, day.raw));
+        outcome.irrigation += gross;
+        outcome.stress_days += (day.depletion > day.raw) as usize;
+        outcome.drainage += day.deep_percolation;
+        outcome.et_lost += day.etc - day.eta;
+    }
+    outcome
+}
+
+// The inputs with the rain from `from` on swapped for simulated seasons, one set per season
+//
+// Weekly totals are spread evenly over the week like the water need projection does, days no
+// model covers keep the rain that was recorded.
+pub fn rain_draws(inputs: &[DayInput], from: f64, models: &[WeekModel], seed: u64, count: usize) -> Vec<Vec<DayInput>> {
+    rain_generator::seasons(models, seed, count)
+        .iter()
+        .map(|rain| {
+            inputs.iter()
+                .map(|input| {
+                    if input.day < from {
+                        return *input;
+                    }
+                    let rain = models.iter().zip(rain)
+                        .find(|(model, _)| model.start <= input.day && input.day < model.start + 7.0)
+                        .map(|(_, amount)| amount / 7.0)
+                        .unwrap_or(input.rain);
+                    DayInput { rain, ..*input }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soil;
+    use crate::dates;
+    use crate::water_balance::EtSource;
+
+    fn setup() -> (BalanceSettings, Vec<SoilLayer>, Vec<DayInput>) {
+        let settings = BalanceSettings { et_source: EtSource::Reference, ..BalanceSettings::default() };
+        let profile = soil::silt_loam();
+        // Four weeks from Monday July 1st, rain on the 10th and a logged inch on the 3rd (net at
+        // the 85% every test runs with)
+        let first = dates::from_ymd(2024, 7, 1);
+        let inputs = (0..28)
+            .map(|offset| {
+                let day = first + offset as f64;
+                DayInput { day, et: 0.28, rain: if offset == 9 { 0.8 } else { 0.0 }, irrigation: if offset == 2 { 0.85 } else { 0.0 }, kc: None }
+            })
+            .collect();
+        (settings, profile, inputs)
+    }
+
+    #[test]
+    fn weekdays_start_on_monday() {
+        assert_eq!(weekday(dates::from_ymd(2024, 7, 1)), 0);
+        assert_eq!(weekday(dates::from_ymd(2024, 7, 4)), 3);
+        assert_eq!(weekday(dates::from_ymd(2024, 7, 7) + 0.5), 6);
+    }
+
+    #[test]
+    fn as_logged_matches_the_water_balance() {
+        let (settings, profile, inputs) = setup();
+        let scenario = &Scenario::defaults()[0];
+        let outcome = run(&settings, &profile, &inputs, 0.5, scenario, inputs[0].day, 0.85);
+        let balance = water_balance::simulate(&settings, &profile, &inputs, 0.5);
+        assert_eq!(outcome.depletion, balance.iter().map(|day| (day.day, day.depletion)).collect::<Vec<_>>());
+        // What was pumped, not what got in
+        assert_eq!(outcome.irrigation, 1.0);
+    }
+
+    #[test]
+    fn one_inch_monday_and_half_twice_put_on_the_same() {
+        let (settings, profile, inputs) = setup();
+        let from = inputs[7].day;
+        let [logged, monday, twice] = [0, 1, 2].map(|index| run(&settings, &profile, &inputs, 0.5, &Scenario::defaults()[index], from, 0.85));
+        // The first week is as logged for every scenario, then three weeks of 1 in
+        assert_eq!(monday.irrigation, 4.0);
+        assert_eq!(twice.irrigation, 4.0);
+        assert_eq!(monday.depletion[..7], logged.depletion[..7]);
+        assert!(monday.depletion.last().unwrap().1 < logged.depletion.last().unwrap().1);
+    }
+
+    #[test]
+    fn blank_efficiency_stays_finite() {
+        let (settings, profile, inputs) = setup();
+        let refill = Scenario { name: "Refill".to_string(), schedule: Schedule::Refill, amount: 0.0, weekdays: [false; 7] };
+        let outcome = run(&settings, &profile, &inputs, 0.5, &refill, inputs[0].day, 0.0);
+        assert!(outcome.irrigation.is_finite());
+        assert!(outcome.depletion.iter().all(|(_, depletion)| depletion.is_finite()));
+    }
+
+    #[test]
+    fn draws_keep_recorded_rain_where_no_week_is_modelled() {
+        let (_, _, mut inputs) = setup();
+        inputs[20].rain = 0.5;
+        let from = inputs[7].day;
+        // Only the week the 10th's rain fell in is modelled, and always dry
+        let week = WeekModel { start: from, proportion_zero: 1.0, mu: 0.0, sigma: 0.0 };
+        let draws = rain_draws(&inputs, from, &[week], 1, 2);
+        assert_eq!(draws.len(), 2);
+        for season in &draws {
+            assert_eq!(season[..7], inputs[..7]);
+            assert_eq!(season[9].rain, 0.0);
+            assert_eq!(season[14..], inputs[14..]);
+        }
+    }
+}
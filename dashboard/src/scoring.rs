@@ -0,0 +1,148 @@
+// Team profit and irrigation water use efficiency, the way TAPS ranks teams
+//
+// Yields come from the harvest results typed in by hand (kept in localStorage). Irrigation is
+// what the team logged, costed with the allocation tracker's pumping numbers.
+
+use std::collections::BTreeMap;
+
+use gloo::storage::{LocalStorage, Storage};
+
+use crate::allocation::AllocationSettings;
+
+// localStorage key the yields live under
+const KEY: &str = "team_yields";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Economics {
+    pub price: f64,       // grain price ($/bu)
+    pub other_costs: f64, // everything but irrigation ($/acre)
+    pub dryland: f64,     // yield without irrigation (bu/acre), 0 if it isn't known
+}
+
+impl Default for Economics {
+    fn default() -> Self {
+        Economics { price: 4.2, other_costs: 650.0, dryland: 0.0 }
+    }
+}
+
+// Yields (bu/acre) typed in so far, by team
+pub fn load_yields() -> BTreeMap<u32, f64> {
+    LocalStorage::get(KEY).unwrap_or_default()
+}
+
+pub fn save_yields(yields: &BTreeMap<u32, f64>) {
+    if let Err(e) = LocalStorage::set(KEY, yields) {
+        gloo::console::error!(format!("Couldn't save the yields: {e}"));
+    }
+}
+
+// One team's numbers, per acre
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Score {
+    pub team: u32,
+    pub grain: Option<f64>, // bu/acre, None until the yield is in
+    pub irrigation: f64,    // gross inches
+    pub revenue: f64,       // $
+    pub irrigation_cost: f64,
+    pub profit: f64,
+    pub iwue: Option<f64>,  // bu per acre-inch of irrigation, over dryland when that's known
+}
+
+pub fn score(team: u32, grain: Option<f64>, irrigation: f64, economics: &Economics, pumping: &AllocationSettings) -> Score {
+    let revenue = grain.unwrap_or(0.0) * economics.price;
+    // An inch on an acre is an acre-inch
+    let irrigation_cost = irrigation * pumping.kwh_per_acre_inch() * pumping.price;
+    let iwue = grain.filter(|_| irrigation > 0.0).map(|grain| (grain - economics.dryland) / irrigation);
+    Score { team, grain, irrigation, revenue, irrigation_cost, profit: revenue - irrigation_cost - economics.other_costs, iwue }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Column {
+    Team,
+    Yield,
+    Irrigation,
+    Revenue,
+    IrrigationCost,
+    Profit,
+    Iwue,
+}
+
+impl Column {
+    pub const ALL: [Column; 7] = [Column::Team, Column::Yield, Column::Irrigation, Column::Revenue, Column::IrrigationCost, Column::Profit, Column::Iwue];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Team => "Team",
+            Column::Yield => "Yield (bu/ac)",
+            Column::Irrigation => "Irrigation (in)",
+            Column::Revenue => "Revenue ($/ac)",
+            Column::IrrigationCost => "Pumping ($/ac)",
+            Column::Profit => "Profit ($/ac)",
+            Column::Iwue => "IWUE (bu/ac-in)",
+        }
+    }
+
+    // Value sorted on, None sorts last whichever way round
+    pub fn value(&self, score: &Score) -> Option<f64> {
+        match self {
+            Column::Team => Some(score.team as f64),
+            Column::Yield => score.grain,
+            Column::Irrigation => Some(score.irrigation),
+            Column::Revenue => score.grain.map(|_| score.revenue),
+            Column::IrrigationCost => Some(score.irrigation_cost),
+            Column::Profit => score.grain.map(|_| score.profit),
+            Column::Iwue => score.iwue,
+        }
+    }
+
+    pub fn text(&self, score: &Score) -> String {
+        match (self, self.value(score)) {
+            (Column::Team, _) => format!("#{}", score.team),
+            (_, None) => "-".to_string(),
+            (Column::Irrigation | Column::Iwue, Some(value)) => format!("{value:.2}"),
+            (_, Some(value)) => format!("{value:.0}"),
+        }
+    }
+}
+
+// Scores in leaderboard order on a column
+pub fn sort(scores: &mut [Score], column: Column, descending: bool) {
+    scores.sort_by(|a, b| match (column.value(a), column.value(b)) {
+        (Some(a), Some(b)) if descending => b.total_cmp(&a),
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.team.cmp(&b.team),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profit_and_iwue() {
+        let economics = Economics { price: 4.0, other_costs: 600.0, dryland: 100.0 };
+        let pumping = AllocationSettings { price: 0.0, ..AllocationSettings::default() };
+        let score = score(2, Some(220.0), 10.0, &economics, &pumping);
+        assert_eq!(score.profit, 880.0 - 600.0);
+        assert_eq!(score.iwue, Some(12.0));
+        assert_eq!(super::score(2, Some(220.0), 0.0, &economics, &pumping).iwue, None);
+    }
+
+    #[test]
+    fn missing_yields_sort_last() {
+        let pumping = AllocationSettings::default();
+        let mut scores: Vec<Score> = [(2, Some(180.0)), (4, None), (5, Some(230.0))]
+            .into_iter()
+            .map(|(team, grain)| score(team, grain, 8.0, &Economics::default(), &pumping))
+            .collect();
+        for descending in [true, false] {
+            sort(&mut scores, Column::Yield, descending);
+            assert_eq!(scores[2].team, 4);
+        }
+        assert_eq!(scores[0].team, 2);
+        sort(&mut scores, Column::Profit, true);
+        assert_eq!(scores.iter().map(|score| score.team).collect::<Vec<_>>(), vec![5, 2, 4]);
+    }
+}
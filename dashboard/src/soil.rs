@@ -0,0 +1,144 @@
+// Soil profiles from the lab texture samples in texture_data.csv
+//
+// The csv is baked into the binary since it is small and never changes during the season.
+
+const TEXTURE_CSV: &str = include_str!("../texture_data.csv");
+
+// One lab sample of a plot's profile
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoilLayer {
+    pub plot_id: String,
+    pub top: f64,    // inches below the surface
+    pub bottom: f64, // inches below the surface
+    pub organic_matter: f64, // %
+    pub texture: String,
+    pub sand: f64, // %
+    pub silt: f64, // %
+    pub clay: f64, // %
+}
+
+impl SoilLayer {
+    // Volumetric water content at -1500 kPa (in/in), Saxton & Rawls (2006)
+    pub fn wilting_point(&self) -> f64 {
+        let (s, c, om) = (self.sand / 100.0, self.clay / 100.0, self.organic_matter);
+        let t = -0.024 * s + 0.487 * c + 0.006 * om + 0.005 * s * om - 0.013 * c * om + 0.068 * s * c + 0.031;
+        t + (0.14 * t - 0.02)
+    }
+
+    // Volumetric water content at -33 kPa (in/in), Saxton & Rawls (2006)
+    pub fn field_capacity(&self) -> f64 {
+        let (s, c, om) = (self.sand / 100.0, self.clay / 100.0, self.organic_matter);
+        let t = -0.251 * s + 0.195 * c + 0.011 * om + 0.006 * s * om - 0.027 * c * om + 0.452 * s * c + 0.299;
+        t + (1.283 * t * t - 0.374 * t - 0.015)
+    }
+
+    // Plant available water held per inch of this layer
+    pub fn available_water(&self) -> f64 {
+        self.field_capacity() - self.wilting_point()
+    }
+
+    fn middle(&self) -> f64 {
+        (self.top + self.bottom) / 2.0
+    }
+}
+
+// Every sample in the csv, in file order
+pub fn layers() -> Vec<SoilLayer> {
+    TEXTURE_CSV.lines()
+        .skip(1) // header
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim().trim_matches('"')).collect();
+            let (top, bottom) = fields.get(5)?.split_once('-')?;
+            Some(SoilLayer {
+                plot_id: fields[0].to_string(),
+                top: top.trim().parse().ok()?,
+                bottom: bottom.trim().parse().ok()?,
+                organic_matter: fields.get(6)?.parse().ok()?,
+                texture: fields.get(7)?.to_string(),
+                sand: fields.get(8)?.parse().ok()?,
+                silt: fields.get(9)?.parse().ok()?,
+                clay: fields.get(10)?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// Plot ids that have samples, in file order
+pub fn plot_ids() -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for layer in layers() {
+        if !ids.contains(&layer.plot_id) {
+            ids.push(layer.plot_id);
+        }
+    }
+    ids
+}
+
+// All the samples of one plot, shallowest first
+pub fn profile(plot_id: &str) -> Vec<SoilLayer> {
+    let mut profile: Vec<SoilLayer> = layers().into_iter().filter(|layer| layer.plot_id == plot_id).collect();
+    profile.sort_by(|a, b| a.top.total_cmp(&b.top));
+    profile
+}
+
+// Sample that best describes the soil at a depth
+//
+// Some plots were spot sampled (4 - 9, 15 - 21...) so there are gaps, the closest sample wins.
+pub fn layer_at(profile: &[SoilLayer], depth: f64) -> Option<&SoilLayer> {
+    profile.iter()
+        .find(|layer| layer.top <= depth && depth < layer.bottom)
+        .or_else(|| profile.iter().min_by(|a, b| (a.middle() - depth).abs().total_cmp(&(b.middle() - depth).abs())))
+}
+
+// Total available water (inches) held between the surface and a depth
+pub fn total_available_water(profile: &[SoilLayer], depth: f64) -> f64 {
+    // Walk down an inch at a time so partial layers and gaps are handled the same way
+    let mut total = 0.0;
+    let mut top = 0.0;
+    while top < depth {
+        let thickness = (depth - top).min(1.0);
+        if let Some(layer) = layer_at(profile, top + thickness / 2.0) {
+            total += layer.available_water() * thickness;
+        }
+        top += thickness;
+    }
+    total
+}
+
+// One uniform silt loam layer, for tests that need a soil but not a particular plot
+#[cfg(test)]
+pub fn silt_loam() -> Vec<SoilLayer> {
+    vec![SoilLayer {
+        plot_id: "test".to_string(),
+        top: 0.0,
+        bottom: 60.0,
+        organic_matter: 2.0,
+        texture: "Silt Loam".to_string(),
+        sand: 20.0,
+        silt: 60.0,
+        clay: 20.0,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saxton_rawls_silt_loam() {
+        let layer = &silt_loam()[0];
+        // About 0.31 at field capacity and 0.14 at wilting for a 20 / 60 / 20 loam
+        assert!((layer.field_capacity() - 0.31).abs() < 0.02, "{}", layer.field_capacity());
+        assert!((layer.wilting_point() - 0.14).abs() < 0.02, "{}", layer.wilting_point());
+        assert!((total_available_water(&silt_loam(), 10.0) - 10.0 * layer.available_water()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn every_plot_has_a_profile() {
+        for id in plot_ids() {
+            let profile = profile(&id);
+            assert!(!profile.is_empty());
+            assert!(profile.windows(2).all(|pair| pair[0].top <= pair[1].top));
+        }
+    }
+}
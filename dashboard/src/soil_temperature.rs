@@ -0,0 +1,144 @@
+// Soil temperature through the AquaSpy profile (F)
+
+use crate::data::aquaspy;
+
+// 4" soil temperature corn and sorghum can go in at, held for PLANTING_DAYS days in a row
+pub const PLANTING: f64 = 50.0;
+pub const PLANTING_DAYS: usize = 3;
+// Root zone temperature roots start to suffer at
+pub const HEAT_STRESS: f64 = 95.0;
+// Deepest sensor counted as root zone for heat stress (inches)
+const HOT_ZONE: f64 = 12.0;
+// Smallest difference (F) between two depths that counts as heat moving
+const FLUX: f64 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DayRange {
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+// Min / mean / max per sensor for every day (readings in time order)
+pub fn daily(readings: &[(f64, Vec<Option<f64>>)]) -> Vec<(f64, Vec<Option<DayRange>>)> {
+    let mut days: Vec<(f64, Vec<Vec<f64>>)> = Vec::new();
+    for (time, values) in readings {
+        if days.last().map(|(day, _)| *day != time.floor()).unwrap_or(true) {
+            days.push((time.floor(), vec![Vec::new(); values.len()]));
+        }
+        let (_, sensors) = days.last_mut().unwrap();
+        for (sensor, value) in sensors.iter_mut().zip(values) {
+            sensor.extend(value);
+        }
+    }
+    days.into_iter()
+        .map(|(day, sensors)| {
+            let ranges = sensors.iter()
+                .map(|values| (!values.is_empty()).then(|| DayRange {
+                    min: values.iter().copied().fold(f64::MAX, f64::min),
+                    mean: values.iter().sum::<f64>() / values.len() as f64,
+                    max: values.iter().copied().fold(f64::MIN, f64::max),
+                }))
+                .collect();
+            (day, ranges)
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flux {
+    Down, // warmer above, the soil is taking heat in
+    Up,   // warmer below, the soil is giving heat back
+    None,
+}
+
+impl Flux {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Flux::Down => "down",
+            Flux::Up => "up",
+            Flux::None => "none",
+        }
+    }
+}
+
+// Which way heat moves between each pair of neighbouring sensors, from their daily means
+pub fn flux(ranges: &[Option<DayRange>]) -> Vec<Flux> {
+    ranges.windows(2)
+        .map(|pair| match (pair[0], pair[1]) {
+            (Some(upper), Some(lower)) if upper.mean - lower.mean > FLUX => Flux::Down,
+            (Some(upper), Some(lower)) if lower.mean - upper.mean > FLUX => Flux::Up,
+            _ => Flux::None,
+        })
+        .collect()
+}
+
+// First day the 4" sensor averaged PLANTING or more for PLANTING_DAYS days running
+pub fn planting_ready(days: &[(f64, Vec<Option<DayRange>>)]) -> Option<f64> {
+    let mut run = 0;
+    for (day, ranges) in days {
+        if ranges[0].map(|range| range.mean >= PLANTING).unwrap_or(false) {
+            run += 1;
+            if run == PLANTING_DAYS {
+                return Some(day - (PLANTING_DAYS - 1) as f64);
+            }
+        } else {
+            run = 0;
+        }
+    }
+    None
+}
+
+// Days the shallow root zone got up to HEAT_STRESS, with the hottest reading
+pub fn heat_stress(days: &[(f64, Vec<Option<DayRange>>)]) -> Vec<(f64, f64)> {
+    days.iter()
+        .filter_map(|(day, ranges)| {
+            let hottest = aquaspy::DEPTHS.iter().zip(ranges)
+                .filter(|(depth, _)| **depth <= HOT_ZONE)
+                .filter_map(|(_, range)| range.map(|range| range.max))
+                .fold(f64::MIN, f64::max);
+            (hottest >= HEAT_STRESS).then_some((*day, hottest))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hourly readings for `days` days from day 0, the 4 in sensor at `shallow(day)` + / - 5 F
+    // over the day and everything deeper at 60 F
+    fn readings(days: usize, shallow: impl Fn(usize) -> f64) -> Vec<(f64, Vec<Option<f64>>)> {
+        (0..days * 24)
+            .map(|hour| {
+                let swing = if (8..20).contains(&(hour % 24)) { 5.0 } else { -5.0 };
+                let mut values = vec![Some(60.0); aquaspy::DEPTHS.len()];
+                values[0] = Some(shallow(hour / 24) + swing);
+                (hour as f64 / 24.0, values)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn daily_ranges_and_flux() {
+        let days = daily(&readings(2, |_| 70.0));
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].1[0], Some(DayRange { min: 65.0, mean: 70.0, max: 75.0 }));
+        let flux = flux(&days[0].1);
+        assert_eq!(flux[0], Flux::Down);
+        assert!(flux[1..].iter().all(|flux| *flux == Flux::None));
+    }
+
+    #[test]
+    fn planting_after_three_warm_days() {
+        let days = daily(&readings(8, |day| if day == 2 { 45.0 } else { 52.0 }));
+        assert_eq!(planting_ready(&days), Some(3.0));
+        assert_eq!(planting_ready(&days[..5]), None);
+    }
+
+    #[test]
+    fn hot_shallow_soil() {
+        let days = daily(&readings(3, |day| if day == 1 { 92.0 } else { 80.0 }));
+        assert_eq!(heat_stress(&days), vec![(1.0, 97.0)]);
+    }
+}
@@ -0,0 +1,82 @@
+// Stress days counted by crop stage, for comparing teams after the season
+//
+// A day can count as more than one kind: too hot, root zone drier than the readily available
+// water (the MAD line), and air dry enough that the crop can't keep up.
+
+use crate::crop::StageSpan;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StressDay {
+    pub day: f64, // excel serial
+    pub heat: bool,
+    pub water: bool,
+    pub vpd: bool,
+}
+
+// Stress days in one stage
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Counts {
+    pub stage: String,
+    pub days: usize, // days with data in the stage
+    pub heat: usize,
+    pub water: usize,
+    pub vpd: usize,
+}
+
+impl Counts {
+    fn add(&mut self, day: &StressDay) {
+        self.days += 1;
+        self.heat += day.heat as usize;
+        self.water += day.water as usize;
+        self.vpd += day.vpd as usize;
+    }
+}
+
+// Counts for every stage in order, days before the first stage get a row of their own
+// and the last row is the whole season
+pub fn by_stage(days: &[StressDay], stages: &[StageSpan]) -> Vec<Counts> {
+    let mut counts: Vec<Counts> = stages.iter().map(|stage| Counts { stage: stage.name.to_string(), ..Counts::default() }).collect();
+    let mut before = Counts { stage: format!("Before {}", stages.first().map(|stage| stage.name).unwrap_or("stages")), ..Counts::default() };
+    let mut season = Counts { stage: "Season".to_string(), ..Counts::default() };
+
+    for day in days {
+        match stages.iter().position(|stage| stage.start <= day.day && day.day < stage.end) {
+            Some(index) => counts[index].add(day),
+            None if stages.first().map(|stage| day.day < stage.start).unwrap_or(true) => before.add(day),
+            None => {}, // past the last stage's data
+        }
+        season.add(day);
+    }
+
+    std::iter::once(before).filter(|before| before.days > 0)
+        .chain(counts)
+        .chain(std::iter::once(season))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_counted_in_their_stage() {
+        let stages = [StageSpan { name: "V6", start: 10.0, end: 20.0 }, StageSpan { name: "VT", start: 20.0, end: 25.0 }];
+        let days: Vec<StressDay> = (5..30)
+            .map(|day| StressDay { day: day as f64, heat: day % 2 == 0, water: day >= 20, vpd: day == 12 })
+            .collect();
+        let counts = by_stage(&days, &stages);
+        assert_eq!(counts.iter().map(|count| count.stage.as_str()).collect::<Vec<&str>>(), vec!["Before V6", "V6", "VT", "Season"]);
+        assert_eq!(counts[0], Counts { stage: "Before V6".to_string(), days: 5, heat: 2, water: 0, vpd: 0 });
+        assert_eq!(counts[1], Counts { stage: "V6".to_string(), days: 10, heat: 5, water: 0, vpd: 1 });
+        assert_eq!(counts[2], Counts { stage: "VT".to_string(), days: 5, heat: 3, water: 5, vpd: 0 });
+        // Days past the last stage only count for the season
+        assert_eq!(counts[3], Counts { stage: "Season".to_string(), days: 25, heat: 12, water: 10, vpd: 1 });
+    }
+
+    #[test]
+    fn no_stages_is_all_season() {
+        let counts = by_stage(&[StressDay { day: 1.0, heat: true, ..StressDay::default() }], &[]);
+        assert_eq!(counts.len(), 2);
+        assert_eq!((counts[0].days, counts[1].heat), (1, 1));
+    }
+}
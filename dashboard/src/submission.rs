@@ -0,0 +1,171 @@
+// Weekly TAPS decision workbook, filled in from the irrigation log and the plan for next week
+//
+// Three sheets: every logged decision with its week, the plan the dashboard recommends for the
+// coming week, and a summary checking the season total against the allocation. The layout is
+// ours, the official TAPS form isn't something we have, so the workbook says so up top.
+
+// Goes on the first row of every sheet
+pub const NOT_OFFICIAL: &str = "Made by the dashboard, this is not the official TAPS form";
+
+use rust_xlsxwriter::{Format, Formula, Workbook, Worksheet, XlsxError};
+
+use crate::dates;
+use crate::irrigation_log::Entry;
+use crate::recommend::{Pivot, PlannedDay};
+
+// Monday of the week a day falls in (excel serial 2 is a Monday)
+pub fn week_start(day: f64) -> f64 {
+    day - (day - 2.0).rem_euclid(7.0)
+}
+
+// Weekly totals (week start, gross inches) of a team's entries
+pub fn weekly(entries: &[&Entry]) -> Vec<(f64, f64)> {
+    let mut weeks: Vec<(f64, f64)> = Vec::new();
+    for entry in entries {
+        let Some(day) = entry.day() else { continue };
+        let week = week_start(day);
+        match weeks.iter_mut().find(|(start, _)| *start == week) {
+            Some((_, total)) => *total += entry.amount,
+            None => weeks.push((week, entry.amount)),
+        }
+    }
+    weeks.sort_by(|a, b| a.0.total_cmp(&b.0));
+    weeks
+}
+
+// Everything wrong with a submission, nothing means it's good to send
+pub fn check(entries: &[&Entry], plan: &[PlannedDay], allocation: f64, pivot: &Pivot) -> Vec<String> {
+    let logged: f64 = entries.iter().fold(0.0, |total, entry| total + entry.amount);
+    let planned: f64 = plan.iter().fold(0.0, |total, day| total + day.irrigation);
+    let mut problems = Vec::new();
+    if logged > allocation {
+        problems.push(format!("Logged irrigation ({logged:.2} in) is already {:.2} in over the {allocation:.2} in allocation.", logged - allocation));
+    } else if logged + planned > allocation {
+        problems.push(format!("Next week's plan ({planned:.2} in) takes the season {:.2} in over the {allocation:.2} in allocation.", logged + planned - allocation));
+    }
+    // A pivot can't put down more than its capacity over a week
+    for (week, total) in weekly(entries) {
+        if total > pivot.capacity * 7.0 + 1e-9 {
+            problems.push(format!("Week of {}: {total:.2} in is more than the pivot can put down in a week ({:.2} in).", dates::label(week), pivot.capacity * 7.0));
+        }
+    }
+    for entry in entries.iter().filter(|entry| entry.amount == 0.0) {
+        problems.push(format!("{}: logged with no water, take it out or fill in the amount.", entry.date));
+    }
+    problems
+}
+
+fn header(sheet: &mut Worksheet, row: u32, titles: &[&str], bold: &Format) -> Result<(), XlsxError> {
+    for (column, title) in titles.iter().enumerate() {
+        sheet.write_string_with_format(row, column as u16, *title, bold)?;
+        sheet.set_column_width(column as u16, 16)?;
+    }
+    Ok(())
+}
+
+// The workbook as .xlsx bytes
+pub fn workbook(team: u32, entries: &[&Entry], plan: &[PlannedDay], allocation: f64, pivot: &Pivot) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let inches = Format::new().set_num_format("0.00");
+
+    // Every decision made so far
+    let sheet = workbook.add_worksheet().set_name("Decisions")?;
+    sheet.write_string_with_format(0, 0, format!("Irrigation decisions - Team #{team} ({NOT_OFFICIAL})"), &bold)?;
+    header(sheet, 1, &["Week of", "Date", "Irrigation (in)", "Method", "Note"], &bold)?;
+    let mut row = 2;
+    let mut logged = 0.0;
+    for entry in entries {
+        let Some(day) = entry.day() else { continue };
+        sheet.write_number_with_format(row, 0, week_start(day), &date)?;
+        sheet.write_number_with_format(row, 1, day, &date)?;
+        sheet.write_number_with_format(row, 2, entry.amount, &inches)?;
+        sheet.write_string(row, 3, entry.method.name())?;
+        sheet.write_string(row, 4, &entry.note)?;
+        logged += entry.amount;
+        row += 1;
+    }
+    sheet.write_string_with_format(row, 1, "Total", &bold)?;
+    // Decisions start on excel row 3, `row` is the (0 based) total row so the last one is `row`
+    if row > 2 {
+        sheet.write_formula_with_format(row, 2, Formula::new(format!("=SUM(C3:C{row})")).set_result(format!("{logged:.2}")), &inches)?;
+    } else {
+        sheet.write_number_with_format(row, 2, 0.0, &inches)?;
+    }
+
+    // What the dashboard recommends for the coming week
+    let sheet = workbook.add_worksheet().set_name("Next week")?;
+    sheet.write_string_with_format(0, 0, NOT_OFFICIAL, &bold)?;
+    header(sheet, 1, &["Date", "Crop ET (in)", "Expected rain (in)", "Irrigation (in)", "Depletion (in)"], &bold)?;
+    for (index, day) in plan.iter().enumerate() {
+        let row = index as u32 + 2;
+        sheet.write_number_with_format(row, 0, day.day, &date)?;
+        sheet.write_number_with_format(row, 1, day.et, &inches)?;
+        sheet.write_number_with_format(row, 2, day.rain, &inches)?;
+        sheet.write_number_with_format(row, 3, day.irrigation, &inches)?;
+        sheet.write_number_with_format(row, 4, day.depletion, &inches)?;
+    }
+
+    // Season against the allocation
+    let planned: f64 = plan.iter().fold(0.0, |total, day| total + day.irrigation);
+    let problems = check(entries, plan, allocation, pivot);
+    let sheet = workbook.add_worksheet().set_name("Summary")?;
+    sheet.set_column_width(0, 28)?;
+    sheet.set_column_width(1, 16)?;
+    let lines: [(&str, f64); 5] = [
+        ("Team", team as f64),
+        ("Allocation (in)", allocation),
+        ("Logged so far (in)", logged),
+        ("Planned next week (in)", planned),
+        ("Left after next week (in)", allocation - logged - planned),
+    ];
+    sheet.write_string_with_format(0, 0, NOT_OFFICIAL, &bold)?;
+    for (index, (name, value)) in lines.iter().enumerate() {
+        let row = index as u32 + 1;
+        sheet.write_string_with_format(row, 0, *name, &bold)?;
+        sheet.write_number(row, 1, *value)?;
+    }
+    sheet.write_string_with_format(6, 0, "Status", &bold)?;
+    sheet.write_string(6, 1, if problems.is_empty() { "OK" } else { "Check" })?;
+    for (index, problem) in problems.iter().enumerate() {
+        sheet.write_string(7 + index as u32, 0, problem)?;
+    }
+
+    workbook.save_to_buffer()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::irrigation_log::Method;
+
+    fn entry(date: &str, amount: f64) -> Entry {
+        Entry { team: 2, date: date.to_string(), amount, method: Method::Pivot, note: String::new() }
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        let monday = dates::from_ymd(2024, 7, 1);
+        assert_eq!(week_start(monday), monday);
+        assert_eq!(week_start(monday + 6.0), monday);
+        assert_eq!(week_start(monday + 7.0), monday + 7.0);
+    }
+
+    #[test]
+    fn check_finds_the_problems() {
+        let pivot = Pivot::default();
+        let good = [entry("2024-07-01", 1.0), entry("2024-07-04", 1.0)];
+        assert!(check(&good.iter().collect::<Vec<_>>(), &[], 14.0, &pivot).is_empty());
+
+        // 2.5 in one week is more than 0.3 in/day can put down, and the season is over 4 in
+        let bad = [entry("2024-07-01", 1.5), entry("2024-07-03", 1.0), entry("2024-07-10", 2.0), entry("2024-07-12", 0.0)];
+        let problems = check(&bad.iter().collect::<Vec<_>>(), &[], 4.0, &pivot);
+        assert_eq!(problems.len(), 3, "{problems:?}");
+    }
+
+    #[test]
+    fn workbook_without_decisions() {
+        assert!(workbook(2, &[], &[], 14.0, &Pivot::default()).is_ok());
+    }
+}
@@ -0,0 +1,104 @@
+// Vapour pressure deficit, dew point and saturation vapour pressure from the Arable weather
+//
+// Arable only gives humidity at the day's max and min temperature, so the actual vapour
+// pressure is the FAO-56 average of the two (eq. 17). Pressures are kPa, dew point F.
+
+use crate::data::arable;
+use crate::reference_et::{self, saturation_vapour_pressure};
+
+// Daily mean VPD (kPa) from which the crop is likely to struggle to keep up
+pub const HIGH_DEMAND: f64 = 2.0;
+
+// Arable columns `air` expects, in order
+pub const COLUMNS: [usize; 4] = [arable::MAX_TEMP, arable::MIN_TEMP, arable::RH_AT_MAX_TEMP, arable::RH_AT_MIN_TEMP];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Air {
+    pub day: f64,        // excel serial
+    pub saturation: f64, // mean of the saturation pressure at max and min temperature
+    pub actual: f64,     // vapour pressure actually in the air
+    pub deficit: f64,    // daily VPD, saturation - actual
+    pub afternoon: f64,  // VPD at the max temperature, the worst of the day
+    pub dew_point: f64,  // F
+}
+
+impl Air {
+    pub fn high_demand(&self) -> bool {
+        self.deficit >= HIGH_DEMAND
+    }
+}
+
+// Dew point (C) of a vapour pressure, the saturation curve turned around
+fn dew_point(actual: f64) -> f64 {
+    let ln = (actual / 0.6108).ln();
+    237.3 * ln / (17.27 - ln)
+}
+
+// Days with every reading, from rows read with COLUMNS
+pub fn air(rows: &[(f64, Vec<Option<f64>>)]) -> Vec<Air> {
+    rows.iter()
+        .filter_map(|(day, values)| {
+            let max = reference_et::celsius(values[0]?);
+            let min = reference_et::celsius(values[1]?);
+            let rh_at_max = values[2]?.clamp(1.0, 100.0);
+            let rh_at_min = values[3]?.clamp(1.0, 100.0);
+
+            let saturation = (saturation_vapour_pressure(max) + saturation_vapour_pressure(min)) / 2.0;
+            let actual = (saturation_vapour_pressure(min) * rh_at_min + saturation_vapour_pressure(max) * rh_at_max) / 200.0;
+            Some(Air {
+                day: day.floor(),
+                saturation,
+                actual,
+                deficit: (saturation - actual).max(0.0),
+                afternoon: saturation_vapour_pressure(max) * (1.0 - rh_at_max / 100.0),
+                dew_point: dew_point(actual) * 9.0 / 5.0 + 32.0,
+            })
+        })
+        .collect()
+}
+
+// Runs of back to back high demand days as (first day, last day)
+pub fn high_demand_runs(days: &[Air]) -> Vec<(f64, f64)> {
+    let mut runs: Vec<(f64, f64)> = Vec::new();
+    for day in days.iter().filter(|day| day.high_demand()) {
+        match runs.last_mut() {
+            Some((_, last)) if day.day - *last <= 1.0 => *last = day.day,
+            _ => runs.push((day.day, day.day)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FAO-56 example 5: 25 / 18 C with 54% humidity at the max and 82% at the min
+    fn example() -> Air {
+        air(&[(45474.5, vec![Some(77.0), Some(64.4), Some(54.0), Some(82.0)])])[0]
+    }
+
+    #[test]
+    fn fao_56_example_5() {
+        let air = example();
+        assert_eq!(air.day, 45474.0);
+        assert!((air.saturation - 2.616).abs() < 0.002);
+        assert!((air.actual - 1.702).abs() < 0.002);
+        assert!((air.deficit - 0.914).abs() < 0.003);
+        assert!(air.afternoon > air.deficit && !air.high_demand());
+    }
+
+    #[test]
+    fn dew_point_is_where_the_air_saturates() {
+        assert!((dew_point(saturation_vapour_pressure(20.0)) - 20.0).abs() < 1e-9);
+        // 1.70 kPa saturates at about 15 C
+        assert!((example().dew_point - 59.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn runs_of_high_demand_days() {
+        let day = |day: f64, deficit: f64| Air { day, saturation: 4.0, actual: 4.0 - deficit, deficit, afternoon: deficit, dew_point: 50.0 };
+        let days = [day(1.0, 2.5), day(2.0, 2.1), day(3.0, 1.0), day(4.0, 3.0), day(6.0, 2.0)];
+        assert_eq!(high_demand_runs(&days), vec![(1.0, 2.0), (4.0, 4.0), (6.0, 6.0)]);
+    }
+}
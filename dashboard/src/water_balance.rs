@@ -0,0 +1,238 @@
+// FAO-56 daily soil water balance (the "bucket" model, chapter 8)
+//
+// Everything here is in inches. Depletion is how far the root zone is below field capacity,
+// 0 means full and the total available water (TAW) means the crop can't pull any more out.
+
+use crate::crop::Crop;
+use crate::data::{aquaspy, arable};
+use crate::dates;
+use crate::soil::{self, SoilLayer};
+
+// Which evapotranspiration drives the model
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EtSource {
+    FieldEt,
+    CanopyEt,
+    Reference, // ASCE ETo worked out from the Arable weather, what FAO-56 Kc values are meant for
+}
+
+impl EtSource {
+    pub const ALL: [EtSource; 3] = [EtSource::FieldEt, EtSource::CanopyEt, EtSource::Reference];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EtSource::FieldEt => "Arable field ET",
+            EtSource::CanopyEt => "Arable canopy ET",
+            EtSource::Reference => "ASCE reference ET (ETo)",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<EtSource> {
+        EtSource::ALL.into_iter().find(|source| source.name() == name)
+    }
+
+    // Arable column it's read from, None when it's calculated
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            EtSource::FieldEt => Some(arable::FIELD_ET),
+            EtSource::CanopyEt => Some(arable::CANOPY_ET),
+            EtSource::Reference => None,
+        }
+    }
+
+    // Arable's ET is already the crop's, a Kc on top of it would count the crop twice
+    pub fn includes_crop(&self) -> bool {
+        *self != EtSource::Reference
+    }
+}
+
+// Where the crop coefficient comes from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KcSource {
+    Fao,  // FAO-56 curve from days after planting
+    Ndvi, // Arable NDVI, falls back to the curve on days without a reading
+}
+
+impl KcSource {
+    pub const ALL: [KcSource; 2] = [KcSource::Fao, KcSource::Ndvi];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KcSource::Fao => "FAO-56 curve",
+            KcSource::Ndvi => "Arable NDVI",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<KcSource> {
+        KcSource::ALL.into_iter().find(|source| source.name() == name)
+    }
+}
+
+// What the user picked in the sidebar
+#[derive(Clone, Debug, PartialEq)]
+pub struct BalanceSettings {
+    pub plot_id: String, // one sampled plot's soil stands in for every team's
+    pub crop: Crop,
+    pub planting: f64, // excel serial
+    pub et_source: EtSource,
+    pub kc_source: KcSource,
+}
+
+impl Default for BalanceSettings {
+    fn default() -> Self {
+        BalanceSettings {
+            plot_id: soil::plot_ids().into_iter().next().unwrap_or_default(),
+            crop: Crop::Corn,
+            planting: dates::from_ymd(2024, 5, 1),
+            et_source: EtSource::Reference,
+            kc_source: KcSource::Fao,
+        }
+    }
+}
+
+// Water coming in and going out on one day
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DayInput {
+    pub day: f64,
+    pub et: f64, // evapotranspiration the crop coefficient is applied to (unless it's already the crop's)
+    pub rain: f64,
+    pub irrigation: f64,
+    pub kc: Option<f64>, // measured crop coefficient, the crop's curve is used when there isn't one
+}
+
+// State of the root zone at the end of one day
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DayBalance {
+    pub day: f64,
+    pub kc: f64,
+    pub root_depth: f64,
+    pub taw: f64,
+    pub raw: f64, // readily available water, depletion past this stresses the crop
+    pub etc: f64, // crop ET with no stress
+    pub eta: f64, // crop ET after the stress coefficient
+    pub ks: f64,
+    pub depletion: f64,
+    pub deep_percolation: f64,
+}
+
+// Run the bucket forward one day at a time
+pub fn simulate(settings: &BalanceSettings, profile: &[SoilLayer], inputs: &[DayInput], initial_depletion: f64) -> Vec<DayBalance> {
+    let mut depletion = initial_depletion.max(0.0);
+    inputs.iter().map(|input| step(settings, profile, input, &mut depletion)).collect()
+}
+
+// One day of the bucket, `depletion` goes in as yesterday's and comes out as today's
+pub fn step(settings: &BalanceSettings, profile: &[SoilLayer], input: &DayInput, depletion: &mut f64) -> DayBalance {
+    let coefficients = settings.crop.coefficients();
+    let days_after_planting = input.day - settings.planting;
+    let kc = if settings.et_source.includes_crop() { 1.0 } else { input.kc.unwrap_or_else(|| coefficients.kc(days_after_planting)) };
+    let root_depth = coefficients.root_depth(days_after_planting);
+    let taw = soil::total_available_water(profile, root_depth);
+    let etc = kc * input.et;
+
+    // FAO-56 table 22 adjusts p for the evaporative demand (which is in mm/day there)
+    let p = (coefficients.depletion_fraction + 0.04 * (5.0 - etc * 25.4)).clamp(0.1, 0.8);
+    let raw = p * taw;
+
+    // eq. 84, water stress coefficient
+    let ks = if *depletion > raw && taw > 0.0 {
+        ((taw - *depletion) / ((1.0 - p) * taw)).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    let eta = ks * etc;
+
+    // eq. 85 and 88, anything that would fill the bucket past field capacity drains out
+    let after = *depletion - input.rain - input.irrigation + eta;
+    let deep_percolation = (-after).max(0.0);
+    *depletion = after.clamp(0.0, taw);
+
+    DayBalance { day: input.day, kc, root_depth, taw, raw, etc, eta, ks, depletion: *depletion, deep_percolation }
+}
+
+// Depletion measured by the AquaSpy probe, for comparing against the model
+//
+// Each sensor stands for the 4 inches of soil above it. Moisture is read as volumetric %
+// and compared against the field capacity of the soil sample at that depth. Days where a
+// sensor inside the root zone is missing are left out.
+pub fn observed_depletion(settings: &BalanceSettings, profile: &[SoilLayer], moisture: &[(f64, Vec<Option<f64>>)]) -> Vec<(f64, f64)> {
+    let coefficients = settings.crop.coefficients();
+
+    moisture.iter()
+        .filter_map(|(day, readings)| {
+            let root_depth = coefficients.root_depth(day - settings.planting);
+            let mut depletion = 0.0;
+            for (depth, reading) in aquaspy::DEPTHS.iter().zip(readings) {
+                let top = depth - 4.0;
+                if top >= root_depth {
+                    break;
+                }
+                let thickness = depth.min(root_depth) - top;
+                let field_capacity = soil::layer_at(profile, top + thickness / 2.0)?.field_capacity();
+                depletion += (field_capacity - (*reading)? / 100.0) * thickness;
+            }
+            Some((*day, depletion))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soil::silt_loam;
+
+    // Mid season, where the corn curve is flat at Kc mid and the roots are all the way down
+    fn settings() -> (BalanceSettings, f64) {
+        let settings = BalanceSettings { plot_id: "test".to_string(), et_source: EtSource::Reference, ..BalanceSettings::default() };
+        let day = settings.planting + 80.0;
+        (settings, day)
+    }
+
+    #[test]
+    fn unstressed_day_uses_full_crop_et() {
+        let (settings, day) = settings();
+        let mut depletion = 0.5;
+        let balance = step(&settings, &silt_loam(), &DayInput { day, et: 0.25, ..DayInput::default() }, &mut depletion);
+        assert_eq!(balance.kc, 1.2);
+        assert_eq!(balance.ks, 1.0);
+        assert!((balance.etc - 0.3).abs() < 1e-9);
+        assert!((depletion - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ks_scales_et_past_raw() {
+        let (settings, day) = settings();
+        let profile = silt_loam();
+        let input = DayInput { day, et: 0.25, ..DayInput::default() };
+        let mut full = 0.0;
+        let reference = step(&settings, &profile, &input, &mut full);
+
+        // Halfway between RAW and TAW, FAO-56 eq. 84 gives Ks = 0.5
+        let start = (reference.raw + reference.taw) / 2.0;
+        let mut depletion = start;
+        let balance = step(&settings, &profile, &input, &mut depletion);
+        assert!((balance.ks - 0.5).abs() < 1e-9);
+        assert!((balance.eta - 0.5 * balance.etc).abs() < 1e-9);
+        assert!((depletion - (start + balance.eta)).abs() < 1e-9);
+        assert_eq!(balance.deep_percolation, 0.0);
+    }
+
+    #[test]
+    fn water_past_field_capacity_drains() {
+        let (settings, day) = settings();
+        let mut depletion = 1.0;
+        let balance = step(&settings, &silt_loam(), &DayInput { day, et: 0.25, rain: 1.0, irrigation: 0.5, kc: None }, &mut depletion);
+        assert_eq!(depletion, 0.0);
+        assert!((balance.deep_percolation - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn arable_et_is_not_multiplied_by_kc() {
+        let (mut settings, day) = settings();
+        settings.et_source = EtSource::FieldEt;
+        let mut depletion = 0.0;
+        let balance = step(&settings, &silt_loam(), &DayInput { day, et: 0.25, kc: Some(1.1), ..DayInput::default() }, &mut depletion);
+        assert_eq!(balance.kc, 1.0);
+        assert!((balance.etc - 0.25).abs() < 1e-9);
+    }
+}